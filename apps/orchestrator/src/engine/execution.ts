@@ -32,6 +32,12 @@ import {
 } from "./filesystem";
 import { GitHubIntegration } from "./github";
 
+/**
+ * Shared Rust crates that component contracts depend on by path, relative to the
+ * project root. In the repo, `contract/<crate>/Cargo.toml` reaches them as `../../../<name>`.
+ */
+const SHARED_CONTRACT_CRATES = ["packages/components/stylus-common"];
+
 export interface ExecutionOptions {
   dryRun?: boolean;
   createGitHubRepo?: boolean;
@@ -360,6 +366,12 @@ export class ExecutionEngine {
         pathContext,
         scope
       );
+      this.copySharedContractCrates(
+        memFs,
+        projectRoot,
+        sourcePath,
+        `${outputPath}/contracts`
+      );
       return;
     }
 
@@ -380,6 +392,60 @@ export class ExecutionEngine {
     const targetPath = `${outputPath}/packages/${dirName}`;
 
     this.copyDirectoryToMemfs(realFs, memFs, sourcePath, targetPath);
+    this.copySharedContractCrates(
+      memFs,
+      projectRoot,
+      sourcePath,
+      `${outputPath}/packages`
+    );
+  }
+
+  /**
+   * Copy the shared Rust crates a component's contracts depend on next to them
+   */
+  private copySharedContractCrates(
+    memFs: ReturnType<typeof createFsFromVolume>,
+    projectRoot: string,
+    sourcePath: string,
+    targetDir: string
+  ): void {
+    const contractPath = path.join(sourcePath, "contract");
+    if (!realFs.existsSync(contractPath)) {
+      return;
+    }
+
+    for (const cratePath of SHARED_CONTRACT_CRATES) {
+      const crateName = path.basename(cratePath);
+      if (!this.dependsOnCrate(contractPath, crateName)) {
+        continue;
+      }
+      console.log(`Copying shared contract crate: ${crateName}`);
+      this.copyDirectoryToMemfs(
+        realFs,
+        memFs,
+        path.join(projectRoot, cratePath),
+        `${targetDir}/${crateName}`
+      );
+    }
+  }
+
+  /**
+   * Whether any Cargo.toml under `dir` depends on the crate `crateName`
+   */
+  private dependsOnCrate(dir: string, crateName: string): boolean {
+    return realFs.readdirSync(dir).some((item) => {
+      if (item === "target" || item.startsWith(".")) {
+        return false;
+      }
+      const itemPath = path.join(dir, item);
+      if (realFs.statSync(itemPath).isDirectory()) {
+        return this.dependsOnCrate(itemPath, crateName);
+      }
+      return (
+        item === "Cargo.toml" &&
+        realFs.readFileSync(itemPath, "utf-8").includes(`${crateName} =`)
+      );
+    });
   }
 
   /**
@@ -475,7 +541,15 @@ export class ExecutionEngine {
           const targetDir = path.dirname(targetPath);
 
           targetFs.mkdirSync(targetDir, { recursive: true });
-          const incomingContent = sourceFs.readFileSync(sourceItem, "utf-8");
+          let incomingContent = sourceFs.readFileSync(sourceItem, "utf-8");
+          if (category === "contract-source" && item === "Cargo.toml") {
+            // Shared crates sit next to the component in the repo, but next to
+            // the contracts in the output (see copySharedContractCrates)
+            incomingContent = incomingContent.replace(
+              /path = "\.\.\/\.\.\/\.\.\/([\w-]+)"/g,
+              'path = "../$1"'
+            );
+          }
 
           // Check if target file exists and needs merging
           let finalContent: string;
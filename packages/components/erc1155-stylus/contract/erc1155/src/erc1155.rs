@@ -2,7 +2,6 @@ use alloc::vec::Vec;
 use stylus_sdk::{
     alloy_primitives::{Address, U256},
     alloy_sol_types::{sol, SolError},
-    evm, msg,
     prelude::*,
 };
 
@@ -17,6 +16,7 @@ sol! {
     event TransferSingle(address indexed operator, address indexed from, address indexed to, uint256 id, uint256 value);
     event TransferBatch(address indexed operator, address indexed from, address indexed to, uint256[] ids, uint256[] values);
     event ApprovalForAll(address indexed account, address indexed operator, bool approved);
+    event URI(string value, uint256 indexed id);
 
     error ERC1155InsufficientBalance(address sender, uint256 balance, uint256 needed, uint256 id);
     error ERC1155InvalidReceiver(address receiver);
@@ -64,7 +64,7 @@ impl Erc1155 {
     }
 
     pub fn set_approval_for_all(&mut self, operator: Address, approved: bool) -> Result<(), Erc1155Error> {
-        let owner = msg::sender();
+        let owner = self.vm().msg_sender();
         if owner == operator {
             return Err(Erc1155Error::InvalidOperator(ERC1155InvalidOperator { operator }));
         }
@@ -72,7 +72,7 @@ impl Erc1155 {
         let mut owner_approvals = self.operator_approvals.setter(owner);
         owner_approvals.insert(operator, approved);
 
-        evm::log(ApprovalForAll {
+        log(self.vm(), ApprovalForAll {
             account: owner,
             operator,
             approved,
@@ -93,7 +93,7 @@ impl Erc1155 {
         value: U256,
         _data: Vec<u8>,
     ) -> Result<(), Erc1155Error> {
-        let operator = msg::sender();
+        let operator = self.vm().msg_sender();
         if from != operator && !self.is_approved_for_all(from, operator) {
             return Err(Erc1155Error::MissingApprovalForAll(ERC1155MissingApprovalForAll {
                 operator,
@@ -118,7 +118,7 @@ impl Erc1155 {
         values: Vec<U256>,
         _data: Vec<u8>,
     ) -> Result<(), Erc1155Error> {
-        let operator = msg::sender();
+        let operator = self.vm().msg_sender();
         if from != operator && !self.is_approved_for_all(from, operator) {
             return Err(Erc1155Error::MissingApprovalForAll(ERC1155MissingApprovalForAll {
                 operator,
@@ -171,8 +171,8 @@ impl Erc1155 {
             to_balance_setter.set(to_balance + value);
         }
 
-        evm::log(TransferSingle {
-            operator: msg::sender(),
+        log(self.vm(), TransferSingle {
+            operator: self.vm().msg_sender(),
             from,
             to,
             id,
@@ -189,7 +189,7 @@ impl Erc1155 {
         ids: Vec<U256>,
         values: Vec<U256>,
     ) -> Result<(), Erc1155Error> {
-        let operator = msg::sender();
+        let operator = self.vm().msg_sender();
         for i in 0..ids.len() {
             let id = ids[i];
             let value = values[i];
@@ -217,7 +217,7 @@ impl Erc1155 {
             }
         }
 
-        evm::log(TransferBatch {
+        log(self.vm(), TransferBatch {
             operator,
             from,
             to,
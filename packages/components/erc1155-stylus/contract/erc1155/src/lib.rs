@@ -4,18 +4,156 @@ extern crate alloc;
 
 pub mod erc1155;
 
-use alloc::vec::Vec;
-use erc1155::Erc1155;
-use stylus_sdk::{alloy_primitives::{Address, U256}, prelude::*};
+use stylus_common::{access_control::{AccessControl, DEFAULT_ADMIN_ROLE}, royalty::Royalty};
+use alloc::{string::String, vec::Vec};
+use erc1155::{Erc1155, Erc1155Error, ERC1155InvalidArrayLength, ERC1155InvalidReceiver, URI};
+use stylus_sdk::{
+    alloy_primitives::{b256, Address, FixedBytes, U256},
+    alloy_sol_types::sol,
+    prelude::*,
+    storage::{StorageBool, StorageString},
+};
+
+/// `keccak256("MINTER_ROLE")`, required to mint tokens
+pub const MINTER_ROLE: FixedBytes<32> =
+    b256!("9f2df0fed2c77648de5860a4cc508cd0818c85b8b8a1ab4ceeef8d981c8956a6");
+
+/// `keccak256("URI_SETTER_ROLE")`, required to update the metadata URI
+pub const URI_SETTER_ROLE: FixedBytes<32> =
+    b256!("7804d923f43a17d325d77e781528e0793b2edd9890ab45fc64efd7b4b427744c");
+
+sol! {
+    error AlreadyInitialized();
+}
+
+#[derive(SolidityError)]
+pub enum My1155Error {
+    AlreadyInitialized(AlreadyInitialized),
+}
 
 #[entrypoint]
 #[storage]
 pub struct My1155 {
     erc1155: Erc1155,
+    #[borrow]
+    access_control: AccessControl,
+    #[borrow]
+    royalty: Royalty,
+    uri: StorageString,
+    initialized: StorageBool,
 }
 
 #[public]
+#[inherit(AccessControl, Royalty)]
 impl My1155 {
+    /// Sets the metadata URI and grants the admin, minter and URI setter roles to the caller
+    /// (can only be called once, so call it in the same script that deploys the contract)
+    pub fn init(&mut self, base_uri: String) -> Result<(), Vec<u8>> {
+        if self.initialized.get() {
+            return Err(My1155Error::AlreadyInitialized(AlreadyInitialized {}).into());
+        }
+        self.initialized.set(true);
+        self.uri.set_str(base_uri);
+
+        let admin = self.vm().msg_sender();
+        self.access_control._grant_role(DEFAULT_ADMIN_ROLE, admin);
+        self.access_control._grant_role(MINTER_ROLE, admin);
+        self.access_control._grant_role(URI_SETTER_ROLE, admin);
+        Ok(())
+    }
+
+    #[selector(name = "MINTER_ROLE")]
+    pub fn minter_role() -> FixedBytes<32> {
+        MINTER_ROLE
+    }
+
+    #[selector(name = "URI_SETTER_ROLE")]
+    pub fn uri_setter_role() -> FixedBytes<32> {
+        URI_SETTER_ROLE
+    }
+
+    /// Metadata URI shared by all token ids (clients substitute `{id}`)
+    pub fn uri(&self, _id: U256) -> String {
+        self.uri.get_string()
+    }
+
+    /// Updates the metadata URI (only callable by URI setters)
+    /// The URI is shared by every token id, so the `URI` event is emitted once with id 0
+    pub fn set_uri(&mut self, new_uri: String) -> Result<(), Vec<u8>> {
+        self.access_control.only_role(URI_SETTER_ROLE)?;
+        self.uri.set_str(&new_uri);
+        log(self.vm(), URI {
+            value: new_uri,
+            id: U256::ZERO,
+        });
+        Ok(())
+    }
+
+    /// Sets the ERC-2981 royalty of every token id without an override, with `fee_bps`
+    /// in basis points of the sale price (only callable by admins)
+    pub fn set_default_royalty(&mut self, receiver: Address, fee_bps: U256) -> Result<(), Vec<u8>> {
+        self.access_control.only_role(DEFAULT_ADMIN_ROLE)?;
+        self.royalty.set_default_royalty(receiver, fee_bps)?;
+        Ok(())
+    }
+
+    /// Removes the default royalty (only callable by admins)
+    pub fn delete_default_royalty(&mut self) -> Result<(), Vec<u8>> {
+        self.access_control.only_role(DEFAULT_ADMIN_ROLE)?;
+        self.royalty.delete_default_royalty();
+        Ok(())
+    }
+
+    /// Overrides the royalty of a single token id (only callable by admins)
+    pub fn set_token_royalty(
+        &mut self,
+        id: U256,
+        receiver: Address,
+        fee_bps: U256,
+    ) -> Result<(), Vec<u8>> {
+        self.access_control.only_role(DEFAULT_ADMIN_ROLE)?;
+        self.royalty.set_token_royalty(id, receiver, fee_bps)?;
+        Ok(())
+    }
+
+    /// Makes a single token id pay the default royalty again (only callable by admins)
+    pub fn reset_token_royalty(&mut self, id: U256) -> Result<(), Vec<u8>> {
+        self.access_control.only_role(DEFAULT_ADMIN_ROLE)?;
+        self.royalty.reset_token_royalty(id);
+        Ok(())
+    }
+
+    /// Mints `value` tokens of `id` to `to` (only callable by minters)
+    pub fn mint(&mut self, to: Address, id: U256, value: U256, _data: Vec<u8>) -> Result<(), Vec<u8>> {
+        self.access_control.only_role(MINTER_ROLE)?;
+        if to.is_zero() {
+            return Err(Erc1155Error::InvalidReceiver(ERC1155InvalidReceiver { receiver: to }).into());
+        }
+        self.erc1155._update_single(Address::ZERO, to, id, value).map_err(|e| e.into())
+    }
+
+    /// Mints `values` tokens of each of `ids` to `to` (only callable by minters)
+    pub fn mint_batch(
+        &mut self,
+        to: Address,
+        ids: Vec<U256>,
+        values: Vec<U256>,
+        _data: Vec<u8>,
+    ) -> Result<(), Vec<u8>> {
+        self.access_control.only_role(MINTER_ROLE)?;
+        if to.is_zero() {
+            return Err(Erc1155Error::InvalidReceiver(ERC1155InvalidReceiver { receiver: to }).into());
+        }
+        if ids.len() != values.len() {
+            return Err(Erc1155Error::InvalidArrayLength(ERC1155InvalidArrayLength {
+                idsLength: U256::from(ids.len()),
+                valuesLength: U256::from(values.len()),
+            })
+            .into());
+        }
+        self.erc1155._update_batch(Address::ZERO, to, ids, values).map_err(|e| e.into())
+    }
+
     pub fn balance_of(&self, account: Address, id: U256) -> U256 {
         self.erc1155.balance_of(account, id)
     }
@@ -53,5 +191,91 @@ impl My1155 {
     ) -> Result<(), Vec<u8>> {
         self.erc1155.safe_batch_transfer_from(from, to, ids, values, data).map_err(|e| e.into())
     }
+
+    /// Whether the contract supports a given standard, including ERC-2981 royalties
+    pub fn supports_interface(interface: FixedBytes<4>) -> bool {
+        const IERC165: u32 = 0x01ffc9a7;
+        const IERC1155: u32 = 0xd9b67a26;
+        const IERC1155_METADATA_URI: u32 = 0x0e89341c;
+
+        matches!(
+            u32::from_be_bytes(interface.0),
+            IERC165 | IERC1155 | IERC1155_METADATA_URI
+        ) || Royalty::supports_interface(interface)
+    }
 }
 
+#[cfg(test)]
+mod test {
+    use super::*;
+    use stylus_sdk::{alloy_primitives::address, alloy_sol_types::SolEvent, testing::*};
+
+    const ALICE: Address = address!("0x000000000000000000000000000000000000a11c");
+    const BOB: Address = address!("0x0000000000000000000000000000000000000b0b");
+
+    #[test]
+    fn test_roles() {
+        let vm = TestVM::default();
+        vm.set_sender(ALICE);
+        let mut contract = My1155::from(&vm);
+        assert!(contract.init("ipfs://collection/{id}.json".into()).is_ok());
+        assert!(contract.access_control.has_role(DEFAULT_ADMIN_ROLE, ALICE));
+        vm.set_sender(BOB);
+        assert!(contract.init("ipfs://other/{id}.json".into()).is_err());
+        assert!(!contract.access_control.has_role(DEFAULT_ADMIN_ROLE, BOB));
+        vm.set_sender(ALICE);
+
+        let id = U256::from(7);
+        assert!(contract.mint(BOB, id, U256::from(3), Vec::new()).is_ok());
+        assert_eq!(U256::from(3), contract.balance_of(BOB, id));
+
+        // Minters and URI setters are separate keys
+        assert!(contract.access_control.grant_role(URI_SETTER_ROLE, BOB).is_ok());
+        assert!(contract.access_control.revoke_role(URI_SETTER_ROLE, ALICE).is_ok());
+        assert!(contract.set_uri("ipfs://alice/{id}.json".into()).is_err());
+
+        vm.set_sender(BOB);
+        assert!(contract.mint(BOB, id, U256::from(1), Vec::new()).is_err());
+        assert!(contract.set_uri("ipfs://bob/{id}.json".into()).is_ok());
+        assert_eq!("ipfs://bob/{id}.json", contract.uri(id));
+        let log = URI {
+            value: "ipfs://bob/{id}.json".into(),
+            id: U256::ZERO,
+        }
+        .encode_log_data();
+        assert!(vm.get_emitted_logs().contains(&(log.topics().to_vec(), log.data.to_vec())));
+    }
+
+    #[test]
+    fn test_royalty() {
+        let vm = TestVM::default();
+        vm.set_sender(ALICE);
+        let mut contract = My1155::from(&vm);
+        assert!(contract.init("ipfs://collection/{id}.json".into()).is_ok());
+
+        let (id, price) = (U256::from(7), U256::from(20_000));
+        vm.set_sender(BOB);
+        assert!(contract.set_default_royalty(BOB, U256::from(1_000)).is_err());
+        vm.set_sender(ALICE);
+        assert!(contract.set_default_royalty(ALICE, U256::from(10_001)).is_err());
+        assert!(contract.set_default_royalty(ALICE, U256::from(1_000)).is_ok());
+        assert_eq!((ALICE, U256::from(2_000)), contract.royalty.royalty_info(id, price));
+
+        assert!(contract.set_token_royalty(id, BOB, U256::from(50)).is_ok());
+        assert_eq!((BOB, U256::from(100)), contract.royalty.royalty_info(id, price));
+        assert!(contract.reset_token_royalty(id).is_ok());
+        assert_eq!((ALICE, U256::from(2_000)), contract.royalty.royalty_info(id, price));
+
+        assert!(My1155::supports_interface(FixedBytes::new([0x2a, 0x55, 0x20, 0x5a])));
+        assert!(My1155::supports_interface(FixedBytes::new([0xd9, 0xb6, 0x7a, 0x26])));
+        assert!(!My1155::supports_interface(FixedBytes::new([0xff, 0xff, 0xff, 0xff])));
+    }
+
+    #[test]
+    fn test_role_ids() {
+        use stylus_sdk::alloy_primitives::keccak256;
+
+        assert_eq!(keccak256("MINTER_ROLE"), My1155::minter_role());
+        assert_eq!(keccak256("URI_SETTER_ROLE"), My1155::uri_setter_role());
+    }
+}
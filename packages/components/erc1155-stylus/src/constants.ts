@@ -29,6 +29,13 @@ export const FACTORY_ADDRESSES: Record<SupportedNetwork, Address> = {
 // ERC1155 Stylus Contract ABI
 // Only includes functions and events that are actually implemented in the contract
 export const ERC1155_ABI = [
+  {
+    type: 'function',
+    name: 'init',
+    inputs: [{ name: 'baseUri', type: 'string' }],
+    outputs: [],
+    stateMutability: 'nonpayable',
+  },
   // View functions
   {
     type: 'function',
@@ -129,6 +136,130 @@ export const ERC1155_ABI = [
       { name: 'approved', type: 'bool', indexed: false },
     ],
   },
+  {
+    type: 'event',
+    name: 'URI',
+    inputs: [
+      { name: 'value', type: 'string', indexed: false },
+      { name: 'id', type: 'uint256', indexed: true },
+    ],
+  },
+  {
+    type: 'function',
+    name: 'hasRole',
+    inputs: [
+      { name: 'role', type: 'bytes32' },
+      { name: 'account', type: 'address' },
+    ],
+    outputs: [{ name: '', type: 'bool' }],
+    stateMutability: 'view',
+  },
+  {
+    type: 'function',
+    name: 'getRoleAdmin',
+    inputs: [{ name: 'role', type: 'bytes32' }],
+    outputs: [{ name: '', type: 'bytes32' }],
+    stateMutability: 'view',
+  },
+  {
+    type: 'function',
+    name: 'grantRole',
+    inputs: [
+      { name: 'role', type: 'bytes32' },
+      { name: 'account', type: 'address' },
+    ],
+    outputs: [],
+    stateMutability: 'nonpayable',
+  },
+  {
+    type: 'function',
+    name: 'revokeRole',
+    inputs: [
+      { name: 'role', type: 'bytes32' },
+      { name: 'account', type: 'address' },
+    ],
+    outputs: [],
+    stateMutability: 'nonpayable',
+  },
+  {
+    type: 'function',
+    name: 'renounceRole',
+    inputs: [
+      { name: 'role', type: 'bytes32' },
+      { name: 'callerConfirmation', type: 'address' },
+    ],
+    outputs: [],
+    stateMutability: 'nonpayable',
+  },
+  {
+    type: 'function',
+    name: 'royaltyInfo',
+    inputs: [
+      { name: 'id', type: 'uint256' },
+      { name: 'salePrice', type: 'uint256' },
+    ],
+    outputs: [
+      { name: 'receiver', type: 'address' },
+      { name: 'royaltyAmount', type: 'uint256' },
+    ],
+    stateMutability: 'view',
+  },
+  {
+    type: 'function',
+    name: 'setDefaultRoyalty',
+    inputs: [
+      { name: 'receiver', type: 'address' },
+      { name: 'feeBps', type: 'uint256' },
+    ],
+    outputs: [],
+    stateMutability: 'nonpayable',
+  },
+  {
+    type: 'function',
+    name: 'deleteDefaultRoyalty',
+    inputs: [],
+    outputs: [],
+    stateMutability: 'nonpayable',
+  },
+  {
+    type: 'function',
+    name: 'setTokenRoyalty',
+    inputs: [
+      { name: 'id', type: 'uint256' },
+      { name: 'receiver', type: 'address' },
+      { name: 'feeBps', type: 'uint256' },
+    ],
+    outputs: [],
+    stateMutability: 'nonpayable',
+  },
+  {
+    type: 'function',
+    name: 'resetTokenRoyalty',
+    inputs: [{ name: 'id', type: 'uint256' }],
+    outputs: [],
+    stateMutability: 'nonpayable',
+  },
+  {
+    type: 'function',
+    name: 'supportsInterface',
+    inputs: [{ name: 'interfaceId', type: 'bytes4' }],
+    outputs: [{ name: '', type: 'bool' }],
+    stateMutability: 'pure',
+  },
+  {
+    type: 'function',
+    name: 'MINTER_ROLE',
+    inputs: [],
+    outputs: [{ name: '', type: 'bytes32' }],
+    stateMutability: 'pure',
+  },
+  {
+    type: 'function',
+    name: 'URI_SETTER_ROLE',
+    inputs: [],
+    outputs: [{ name: '', type: 'bytes32' }],
+    stateMutability: 'pure',
+  },
 ] as const;
 
 // Token Factory ABI (for future factory deployment)
@@ -69,7 +69,7 @@ export async function initializeMultiToken(
   const wallet = new ethers.Wallet(privateKey, provider);
   const contract = new ethers.Contract(contractAddress, ERC1155_ABI, wallet);
 
-  const tx = await contract.initialize(baseUri, wallet.address);
+  const tx = await contract.init(baseUri);
   const receipt = await tx.wait();
   
   return receipt.hash;
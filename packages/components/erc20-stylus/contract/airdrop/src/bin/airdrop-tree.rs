@@ -0,0 +1,27 @@
+//! Builds an airdrop Merkle tree from a CSV of `account,amount` rows
+//! and prints the root and every proof as JSON.
+//!
+//! Usage: cargo run --features tree --bin airdrop-tree -- <claims.csv>
+
+use airdrop_stylus::tree::MerkleTree;
+use std::{env, fs, process};
+
+fn main() {
+    let Some(path) = env::args().nth(1) else {
+        eprintln!("usage: airdrop-tree <claims.csv>");
+        process::exit(2);
+    };
+
+    let tree = fs::read_to_string(&path)
+        .map_err(|err| err.to_string())
+        .and_then(|csv| MerkleTree::from_csv(&csv).map_err(|err| err.to_string()))
+        .unwrap_or_else(|err| {
+            eprintln!("{path}: {err}");
+            process::exit(1);
+        });
+    let Some(json) = tree.to_json() else {
+        eprintln!("{path}: the amounts add up to more than 2^256 - 1");
+        process::exit(1);
+    };
+    println!("{json}");
+}
@@ -0,0 +1,312 @@
+//! Implementation of a Merkle-proof airdrop distributor
+//!
+//! [`MerkleDistributor`] holds a balance of an ERC-20 token and a Merkle root
+//! committing to a list of `(index, account, amount)` claims. Anyone can
+//! submit a claim with its proof, and the tokens are sent to `account`.
+//! Claimed indices are tracked in a bitmap, so each claim only pays out once.
+//!
+//! Leaves are `keccak256(abi.encodePacked(index, account, amount))` and pairs
+//! are hashed in sorted order, which matches the usual Solidity distributors.
+//! The host-side [`tree`] module (behind the `tree` feature) builds the tree
+//! and proofs from a CSV file.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(feature = "export-abi", test)), no_main)]
+extern crate alloc;
+
+// Modules and imports
+#[cfg(all(any(feature = "tree", test), not(target_arch = "wasm32")))]
+pub mod tree;
+
+use alloc::vec::Vec;
+use alloy_primitives::{Address, FixedBytes, U256};
+use alloy_sol_types::{sol, SolCall, SolValue};
+use stylus_sdk::{crypto::keccak, prelude::*, stylus_core::calls::context::Call};
+
+// Interface of the distributed token
+sol! {
+    interface IERC20 {
+        function transfer(address to, uint256 value) external returns (bool);
+    }
+}
+
+// Define the entrypoint as a Solidity storage object. The sol_storage! macro
+// will generate Rust-equivalent structs with all fields mapped to Solidity-equivalent
+// storage slots and types.
+sol_storage! {
+    #[entrypoint]
+    pub struct MerkleDistributor {
+        /// The distributed token
+        address token;
+        /// Root of the tree of claims
+        bytes32 merkle_root;
+        /// Maps words of 256 claim indices to a bitmap of which were claimed
+        mapping(uint256 => uint256) claimed_bitmap;
+        /// Whether init() has been called
+        bool initialized;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event Claimed(uint256 index, address account, uint256 amount);
+
+    error AlreadyInitialized();
+    error AlreadyClaimed(uint256 index);
+    error InvalidProof();
+    error SafeERC20FailedOperation(address token);
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum DistributorError {
+    AlreadyInitialized(AlreadyInitialized),
+    AlreadyClaimed(AlreadyClaimed),
+    InvalidProof(InvalidProof),
+    SafeErc20FailedOperation(SafeERC20FailedOperation),
+}
+
+/// Leaf of the claim of `amount` tokens by `account` at `index`
+pub fn leaf_hash(index: U256, account: Address, amount: U256) -> FixedBytes<32> {
+    keccak((index, account, amount).abi_encode_packed())
+}
+
+/// Parent of two nodes, hashed in sorted order so proofs don't need to record sides
+pub fn hash_pair(a: FixedBytes<32>, b: FixedBytes<32>) -> FixedBytes<32> {
+    let (low, high) = if a <= b { (a, b) } else { (b, a) };
+    keccak([low.as_slice(), high.as_slice()].concat())
+}
+
+/// Whether `proof` shows that `leaf` is part of the tree with `root`
+pub fn verify(proof: &[FixedBytes<32>], root: FixedBytes<32>, leaf: FixedBytes<32>) -> bool {
+    proof.iter().fold(leaf, |node, sibling| hash_pair(node, *sibling)) == root
+}
+
+// These methods aren't exposed to other contracts
+impl MerkleDistributor {
+    /// Checks the claim against the Merkle root and marks it as claimed
+    /// (invoked by the external claim() function, before sending the tokens)
+    pub fn _claim(
+        &mut self,
+        index: U256,
+        account: Address,
+        amount: U256,
+        proof: &[FixedBytes<32>],
+    ) -> Result<(), DistributorError> {
+        if self.is_claimed(index) {
+            return Err(DistributorError::AlreadyClaimed(AlreadyClaimed { index }));
+        }
+        if !verify(proof, self.merkle_root.get(), leaf_hash(index, account, amount)) {
+            return Err(DistributorError::InvalidProof(InvalidProof {}));
+        }
+
+        let word = index >> 8;
+        let bit = U256::from(1) << (index & U256::from(0xff));
+        let bitmap = self.claimed_bitmap.get(word);
+        self.claimed_bitmap.insert(word, bitmap | bit);
+        log(self.vm(), Claimed {
+            index,
+            account,
+            amount,
+        });
+        Ok(())
+    }
+}
+
+// These methods are external to other contracts
+#[public]
+impl MerkleDistributor {
+    /// Sets the distributed token and the root of the tree of claims
+    pub fn init(
+        &mut self,
+        token: Address,
+        merkle_root: FixedBytes<32>,
+    ) -> Result<(), DistributorError> {
+        if self.initialized.get() {
+            return Err(DistributorError::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        self.token.set(token);
+        self.merkle_root.set(merkle_root);
+        self.initialized.set(true);
+        Ok(())
+    }
+
+    /// The distributed token
+    pub fn token(&self) -> Address {
+        self.token.get()
+    }
+
+    /// Root of the tree of claims
+    pub fn merkle_root(&self) -> FixedBytes<32> {
+        self.merkle_root.get()
+    }
+
+    /// Whether the claim at `index` has been paid out
+    pub fn is_claimed(&self, index: U256) -> bool {
+        let bitmap = self.claimed_bitmap.get(index >> 8);
+        bitmap.bit((index & U256::from(0xff)).to())
+    }
+
+    /// Sends `amount` tokens to `account`, given a proof of the claim at `index`
+    /// (tokens that return nothing from `transfer`, like USDT, are supported)
+    pub fn claim(
+        &mut self,
+        index: U256,
+        account: Address,
+        amount: U256,
+        proof: Vec<FixedBytes<32>>,
+    ) -> Result<(), Vec<u8>> {
+        self._claim(index, account, amount, &proof)?;
+
+        let token = self.token.get();
+        let calldata = IERC20::transferCall { to: account, value: amount }.abi_encode();
+        let returned = self.vm().call(&Call::new(), token, &calldata)?;
+        let transferred = if returned.is_empty() {
+            // An empty return value only counts as a success from a contract
+            self.vm().code_size(token) > 0
+        } else {
+            IERC20::transferCall::abi_decode_returns(&returned, true)
+                .is_ok_and(|returned| returned._0)
+        };
+        if !transferred {
+            return Err(
+                DistributorError::SafeErc20FailedOperation(SafeERC20FailedOperation { token })
+                    .into(),
+            );
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::tree::MerkleTree;
+    use alloy_primitives::{address, keccak256};
+    use alloy_sol_types::{SolError, SolEvent};
+    use stylus_sdk::testing::*;
+
+    const ALICE: Address = address!("0x000000000000000000000000000000000000a11c");
+    const BOB: Address = address!("0x0000000000000000000000000000000000000b0b");
+    const CAROL: Address = address!("0x0000000000000000000000000000000000000c0c");
+    const TOKEN: Address = address!("0x0000000000000000000000000000000000007070");
+
+    const CSV: &str = "account,amount
+0x000000000000000000000000000000000000a11c,100
+0x0000000000000000000000000000000000000b0b,200
+0x0000000000000000000000000000000000000c0c,300
+";
+
+    /// Makes TOKEN answer a transfer of `value` from the distributor to `to` with `returned`
+    fn mock_transfer(vm: &TestVM, to: Address, value: u64, returned: Result<Vec<u8>, Vec<u8>>) {
+        let value = U256::from(value);
+        let calldata = IERC20::transferCall { to, value }.abi_encode();
+        vm.mock_call(TOKEN, calldata, returned);
+    }
+
+    #[test]
+    fn test_leaf_hash() {
+        // abi.encodePacked(uint256, address, uint256) is 32 + 20 + 32 bytes
+        let mut packed = U256::from(1).to_be_bytes::<32>().to_vec();
+        packed.extend_from_slice(ALICE.as_slice());
+        packed.extend_from_slice(&U256::from(100).to_be_bytes::<32>());
+        assert_eq!(keccak256(packed), leaf_hash(U256::from(1), ALICE, U256::from(100)));
+
+        let (a, b) = (keccak256("a"), keccak256("b"));
+        assert_eq!(hash_pair(a, b), hash_pair(b, a));
+    }
+
+    #[test]
+    fn test_claim() {
+        let tree = MerkleTree::from_csv(CSV).unwrap();
+        let vm = TestVM::default();
+        let mut distributor = MerkleDistributor::from(&vm);
+        assert!(distributor.init(TOKEN, tree.root()).is_ok());
+        assert!(distributor.init(TOKEN, tree.root()).is_err());
+
+        let bob_proof = tree.proof(1).unwrap();
+        assert!(!distributor.is_claimed(U256::from(1)));
+
+        // The proof only matches the exact claim
+        assert!(matches!(
+            distributor._claim(U256::from(1), BOB, U256::from(201), &bob_proof),
+            Err(DistributorError::InvalidProof(_))
+        ));
+        assert!(matches!(
+            distributor._claim(U256::from(0), BOB, U256::from(200), &bob_proof),
+            Err(DistributorError::InvalidProof(_))
+        ));
+
+        assert!(distributor._claim(U256::from(1), BOB, U256::from(200), &bob_proof).is_ok());
+        assert!(distributor.is_claimed(U256::from(1)));
+        assert!(!distributor.is_claimed(U256::from(0)));
+        assert!(matches!(
+            distributor._claim(U256::from(1), BOB, U256::from(200), &bob_proof),
+            Err(DistributorError::AlreadyClaimed(_))
+        ));
+
+        let alice_proof = tree.proof(0).unwrap();
+        assert!(distributor._claim(U256::from(0), ALICE, U256::from(100), &alice_proof).is_ok());
+    }
+
+    #[test]
+    fn test_claim_transfer() {
+        let tree = MerkleTree::from_csv(CSV).unwrap();
+        let vm = TestVM::default();
+        vm.set_code(TOKEN, vec![0xfe]);
+        let mut distributor = MerkleDistributor::from(&vm);
+        assert!(distributor.init(TOKEN, tree.root()).is_ok());
+
+        let bob_proof = tree.proof(1).unwrap();
+        mock_transfer(&vm, BOB, 200, Ok(true.abi_encode()));
+        assert!(distributor.claim(U256::from(1), BOB, U256::from(200), bob_proof.clone()).is_ok());
+        assert!(distributor.is_claimed(U256::from(1)));
+        let claimed = Claimed {
+            index: U256::from(1),
+            account: BOB,
+            amount: U256::from(200),
+        }
+        .encode_log_data();
+        assert!(vm
+            .get_emitted_logs()
+            .contains(&(claimed.topics().to_vec(), claimed.data.to_vec())));
+        assert_eq!(
+            Err(AlreadyClaimed { index: U256::from(1) }.abi_encode()),
+            distributor.claim(U256::from(1), BOB, U256::from(200), bob_proof)
+        );
+
+        // A token reporting a failed transfer is refused
+        let alice_proof = tree.proof(0).unwrap();
+        mock_transfer(&vm, ALICE, 100, Ok(false.abi_encode()));
+        assert_eq!(
+            Err(SafeERC20FailedOperation { token: TOKEN }.abi_encode()),
+            distributor.claim(U256::from(0), ALICE, U256::from(100), alice_proof)
+        );
+
+        // And a reverting token's revert data is bubbled up
+        let carol_proof = tree.proof(2).unwrap();
+        mock_transfer(&vm, CAROL, 300, Err(b"paused".to_vec()));
+        assert_eq!(
+            Err(b"paused".to_vec()),
+            distributor.claim(U256::from(2), CAROL, U256::from(300), carol_proof)
+        );
+    }
+
+    #[test]
+    fn test_claimed_bitmap() {
+        let vm = TestVM::default();
+        let mut distributor = MerkleDistributor::from(&vm);
+
+        // A single-leaf tree's root is the leaf itself
+        let index = U256::from(300);
+        assert!(distributor.init(TOKEN, leaf_hash(index, ALICE, U256::from(1))).is_ok());
+        assert!(distributor._claim(index, ALICE, U256::from(1), &[]).is_ok());
+
+        assert!(distributor.is_claimed(index));
+        assert!(!distributor.is_claimed(U256::from(44)));
+        assert!(!distributor.is_claimed(U256::from(301)));
+        assert_eq!(U256::from(1) << 44, distributor.claimed_bitmap.get(U256::from(1)));
+    }
+}
@@ -0,0 +1,253 @@
+//! Host-side builder for airdrop Merkle trees
+//!
+//! [`MerkleTree`] turns a list of claims into the root passed to
+//! [`MerkleDistributor::init`](crate::MerkleDistributor) and the proofs
+//! passed to `claim`. Claims are read from a CSV of `account,amount` rows,
+//! and each claim's index is its position among the claims, so neither the
+//! header nor blank lines count.
+//!
+//! This module needs `std` and is only compiled with the `tree` feature on
+//! non-wasm targets; it is never part of the deployed contract.
+
+// Imported packages
+use crate::{hash_pair, leaf_hash};
+use alloy_primitives::{Address, FixedBytes, U256};
+use std::{collections::HashSet, fmt, string::String, vec::Vec};
+
+/// One entry of the airdrop
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Claim {
+    pub index: U256,
+    pub account: Address,
+    pub amount: U256,
+}
+
+/// Ways a CSV of claims may be rejected
+#[derive(Debug, PartialEq, Eq)]
+pub enum CsvError {
+    /// The CSV has no claims
+    Empty,
+    /// A row isn't `account,amount` (lines are 1-based)
+    InvalidRow { line: usize, row: String },
+    /// An account appears on more than one row
+    DuplicateAccount { line: usize, account: Address },
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CsvError::Empty => write!(f, "no claims found"),
+            CsvError::InvalidRow { line, row } => {
+                write!(f, "line {line}: expected `account,amount`, found `{row}`")
+            }
+            CsvError::DuplicateAccount { line, account } => {
+                write!(f, "line {line}: duplicate account {account}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for CsvError {}
+
+/// Merkle tree over a list of claims
+pub struct MerkleTree {
+    claims: Vec<Claim>,
+    /// Hashes of each level, from the leaves up to the root
+    layers: Vec<Vec<FixedBytes<32>>>,
+}
+
+impl MerkleTree {
+    /// Builds the tree, indexing `(account, amount)` pairs in order
+    pub fn from_claims(entries: impl IntoIterator<Item = (Address, U256)>) -> Self {
+        let claims: Vec<Claim> = entries
+            .into_iter()
+            .enumerate()
+            .map(|(index, (account, amount))| Claim {
+                index: U256::from(index),
+                account,
+                amount,
+            })
+            .collect();
+
+        let leaves = claims
+            .iter()
+            .map(|claim| leaf_hash(claim.index, claim.account, claim.amount))
+            .collect();
+        let mut layers: Vec<Vec<FixedBytes<32>>> = vec![leaves];
+        while layers.last().is_some_and(|layer| layer.len() > 1) {
+            // An odd node out is carried up to the next level as is
+            let next = layers[layers.len() - 1]
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => hash_pair(*a, *b),
+                    [a] => *a,
+                    _ => unreachable!(),
+                })
+                .collect();
+            layers.push(next);
+        }
+
+        Self { claims, layers }
+    }
+
+    /// Builds the tree from CSV rows of `account,amount`, with an optional header
+    pub fn from_csv(csv: &str) -> Result<Self, CsvError> {
+        let mut entries = Vec::new();
+        let mut seen = HashSet::new();
+        for (line, row) in csv.lines().enumerate() {
+            let line = line + 1;
+            let row = row.trim();
+            if row.is_empty() || (line == 1 && !row.starts_with("0x")) {
+                continue;
+            }
+
+            let invalid = || CsvError::InvalidRow {
+                line,
+                row: row.into(),
+            };
+            let (account, amount) = row.split_once(',').ok_or_else(invalid)?;
+            let account: Address = account.trim().parse().map_err(|_| invalid())?;
+            let amount: U256 = amount.trim().parse().map_err(|_| invalid())?;
+            if !seen.insert(account) {
+                return Err(CsvError::DuplicateAccount { line, account });
+            }
+            entries.push((account, amount));
+        }
+
+        if entries.is_empty() {
+            return Err(CsvError::Empty);
+        }
+        Ok(Self::from_claims(entries))
+    }
+
+    /// Root to store in the distributor
+    pub fn root(&self) -> FixedBytes<32> {
+        self.layers
+            .last()
+            .and_then(|layer| layer.first())
+            .copied()
+            .unwrap_or_default()
+    }
+
+    /// All claims, ordered by index
+    pub fn claims(&self) -> &[Claim] {
+        &self.claims
+    }
+
+    /// Sum of all claimed amounts, i.e. the balance the distributor needs,
+    /// or `None` if it doesn't fit in a `U256`
+    pub fn total(&self) -> Option<U256> {
+        self.claims
+            .iter()
+            .try_fold(U256::ZERO, |total, claim| total.checked_add(claim.amount))
+    }
+
+    /// Proof of the claim at `index`, or `None` if there is no such claim
+    pub fn proof(&self, index: usize) -> Option<Vec<FixedBytes<32>>> {
+        if index >= self.claims.len() {
+            return None;
+        }
+
+        let mut proof = Vec::new();
+        let mut pos = index;
+        for layer in &self.layers[..self.layers.len() - 1] {
+            if let Some(sibling) = layer.get(pos ^ 1) {
+                proof.push(*sibling);
+            }
+            pos /= 2;
+        }
+        Some(proof)
+    }
+
+    /// Root, total and every claim with its proof, keyed by account, as JSON,
+    /// or `None` if the total doesn't fit in a `U256`
+    pub fn to_json(&self) -> Option<String> {
+        let total = self.total()?;
+        let claims: Vec<String> = self
+            .claims
+            .iter()
+            .map(|claim| {
+                let index: usize = claim.index.to();
+                let proof: Vec<String> = self
+                    .proof(index)
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|node| format!("\"{node}\""))
+                    .collect();
+                format!(
+                    "\"{}\":{{\"index\":{},\"amount\":\"{}\",\"proof\":[{}]}}",
+                    claim.account,
+                    index,
+                    claim.amount,
+                    proof.join(",")
+                )
+            })
+            .collect();
+
+        Some(format!(
+            "{{\"merkleRoot\":\"{}\",\"tokenTotal\":\"{}\",\"claims\":{{{}}}}}",
+            self.root(),
+            total,
+            claims.join(",")
+        ))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::verify;
+
+    #[test]
+    fn test_every_proof_verifies() {
+        for size in 1..=9 {
+            let tree = MerkleTree::from_claims(
+                (0..size).map(|i| (Address::with_last_byte(i as u8 + 1), U256::from(i * 10))),
+            );
+            for claim in tree.claims() {
+                let proof = tree.proof(claim.index.to()).unwrap();
+                let leaf = leaf_hash(claim.index, claim.account, claim.amount);
+                assert!(verify(&proof, tree.root(), leaf));
+            }
+            assert_eq!(None, tree.proof(size));
+        }
+    }
+
+    #[test]
+    fn test_from_csv() {
+        let csv = "account,amount
+0x000000000000000000000000000000000000a11c, 100
+
+0x0000000000000000000000000000000000000b0b,0x10
+";
+        let tree = MerkleTree::from_csv(csv).unwrap();
+        assert_eq!(2, tree.claims().len());
+        assert_eq!(U256::from(16), tree.claims()[1].amount);
+        assert_eq!(Some(U256::from(116)), tree.total());
+        assert!(tree.to_json().unwrap().contains("\"tokenTotal\":\"116\""));
+        assert_eq!(U256::from(1), tree.claims()[1].index);
+
+        assert_eq!(Some(CsvError::Empty), MerkleTree::from_csv("account,amount\n").err());
+        assert!(matches!(
+            MerkleTree::from_csv("0x000000000000000000000000000000000000a11c"),
+            Err(CsvError::InvalidRow { line: 1, .. })
+        ));
+        assert!(matches!(
+            MerkleTree::from_csv(
+                "0x000000000000000000000000000000000000a11c,1
+0x000000000000000000000000000000000000A11C,2"
+            ),
+            Err(CsvError::DuplicateAccount { line: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn test_total_overflow() {
+        let tree = MerkleTree::from_claims([
+            (Address::with_last_byte(1), U256::MAX),
+            (Address::with_last_byte(2), U256::from(1)),
+        ]);
+        assert_eq!(None, tree.total());
+        assert_eq!(None, tree.to_json());
+    }
+}
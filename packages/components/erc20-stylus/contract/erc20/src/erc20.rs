@@ -3,42 +3,125 @@
 //! The eponymous [`Erc20`] type provides all the standard methods,
 //! and is intended to be inherited by other contract types.
 //!
-//! You can configure the behavior of [`Erc20`] via the [`Erc20Params`] trait,
-//! which allows specifying the name, symbol, and decimals of the token.
+//! The token name and symbol are kept in storage and set once through
+//! [`Erc20::init`], so a single deployed contract can back any token.
+//! You can configure the remaining behavior of [`Erc20`] via the
+//! [`Erc20Params`] trait, which allows specifying the decimals of the token
+//! and an optional cap on its total supply, and customise balance movements
+//! via the [`Erc20Hooks`] trait, whose hooks run around every [`Erc20::_update`]
+//! and keep their own state in [`Erc20Hooks::Storage`].
+//!
+//! Gasless approvals are supported through EIP-2612 `permit`, and balances
+//! double as voting power that holders can delegate (ERC20Votes-style), with
+//! a checkpointed history so that past votes can be queried by timestamp.
+//! The token itself can be flash minted through ERC-3156 `flashLoan`, and
+//! ERC-1363 `transferAndCall` / `approveAndCall` notify receiving contracts,
+//! and ERC-7802 `crosschainMint` / `crosschainBurn` let bridges move it across chains.
+//!
+//! Tokens can also opt into a transfer fee through [`Erc20Hooks::transfer_fee`],
+//! carved out of every transfer and sent to a fee recipient.
 //!
 //! Note that this code is unaudited and not fit for production use.
 
 // Imported packages
-use alloc::string::String;
-use alloy_primitives::{Address, U256};
-use alloy_sol_types::sol;
-use core::marker::PhantomData;
+use alloc::{string::String, vec::Vec};
+use alloy_primitives::{aliases::U8, b256, Address, FixedBytes, U256};
+use alloy_sol_types::{sol, SolCall, SolValue};
+use core::{borrow::BorrowMut, marker::PhantomData};
 use stylus_sdk::{
-    evm,
-    msg,
-    prelude::*,
+    abi::Bytes, crypto::keccak, prelude::*, storage::StorageType, stylus_core::calls::context::Call,
 };
 
+use crate::votes::{Checkpoint, Checkpoints};
+
 pub trait Erc20Params {
-    /// Immutable token name
-    const NAME: &'static str;
+    /// Token decimals, written to storage by `init`
+    const DECIMALS: u8;
 
-    /// Immutable token symbol
-    const SYMBOL: &'static str;
+    /// Immutable cap on the total supply (`None` leaves the supply uncapped)
+    const MAX_SUPPLY: Option<U256> = None;
 
-    /// Immutable token decimals
-    const DECIMALS: u8;
+    /// Fee charged on flash loans, in basis points of the borrowed amount
+    const FLASH_FEE_BPS: u64 = 0;
+
+    /// Whether the token can be flash minted (when disabled, `flashLoan` reverts
+    /// and `maxFlashLoan` is zero, as for an unsupported token)
+    const FLASH_LOANS: bool = true;
+}
+
+/// Storage for hooks that keep no state of their own (it takes no slot)
+pub type NoStorage = PhantomData<()>;
+
+/// Hooks run around every balance movement (transfers, mints and burns).
+/// They default to a no-op, so contracts only override what they need.
+pub trait Erc20Hooks: Sized {
+    /// State kept by the hooks, laid out inside [`Erc20`]
+    /// (use [`NoStorage`] when they don't need any)
+    type Storage: StorageType;
+
+    /// Called before `value` tokens move from `from` to `to`
+    /// (`from` is zero on mints, `to` is zero on burns); returning an error aborts the movement
+    fn before_update(
+        _token: &mut Erc20<Self>,
+        _from: Address,
+        _to: Address,
+        _value: U256,
+    ) -> Result<(), Erc20Error> {
+        Ok(())
+    }
+
+    /// Called after `value` tokens moved from `from` to `to`
+    /// (`from` is zero on mints, `to` is zero on burns); returning an error aborts the movement
+    fn after_update(
+        _token: &mut Erc20<Self>,
+        _from: Address,
+        _to: Address,
+        _value: U256,
+    ) -> Result<(), Erc20Error> {
+        Ok(())
+    }
+
+    /// Fee charged on a transfer of `value` tokens from `from` to `to`, together with the
+    /// account receiving it; the fee is carved out of `value` (none by default)
+    fn transfer_fee(
+        _token: &Erc20<Self>,
+        _from: Address,
+        _to: Address,
+        _value: U256,
+    ) -> Option<(Address, U256)> {
+        None
+    }
 }
 
 sol_storage! {
     /// Erc20 implements all ERC-20 methods.
-    pub struct Erc20<T> {
+    pub struct Erc20<T: Erc20Hooks> {
         /// Maps users to balances
         mapping(address => uint256) balances;
         /// Maps users to a mapping of each spender's allowance
         mapping(address => mapping(address => uint256)) allowances;
         /// The total supply of the token
         uint256 total_supply;
+        /// The token name
+        string name;
+        /// The token symbol
+        string symbol;
+        /// The token decimals
+        uint8 decimals;
+        /// Whether `init` has already been called
+        bool initialized;
+        /// Whether transfers, mints and burns are currently halted
+        bool paused;
+        /// Maps owners to their next `permit` / `delegateBySig` nonce
+        mapping(address => uint256) nonces;
+        /// Maps accounts to the delegate holding their voting power
+        mapping(address => address) delegates;
+        /// Maps delegates to their history of voting power
+        mapping(address => Checkpoint[]) delegate_checkpoints;
+        /// History of the total supply
+        Checkpoint[] total_supply_checkpoints;
+        /// State of the [`Erc20Hooks`]
+        T::Storage hooks;
         /// Used to allow [`Erc20Params`]
         PhantomData<T> phantom;
     }
@@ -48,9 +131,49 @@ sol_storage! {
 sol! {
     event Transfer(address indexed from, address indexed to, uint256 value);
     event Approval(address indexed owner, address indexed spender, uint256 value);
+    event Paused(address account);
+    event Unpaused(address account);
+    event DelegateChanged(address indexed delegator, address indexed from_delegate, address indexed to_delegate);
+    event DelegateVotesChanged(address indexed delegate, uint256 previous_votes, uint256 new_votes);
+    event FeeCollected(address indexed from, address indexed fee_recipient, uint256 fee);
+    event CrosschainMint(address indexed to, uint256 amount, address indexed sender);
+    event CrosschainBurn(address indexed from, uint256 amount, address indexed sender);
 
     error InsufficientBalance(address from, uint256 have, uint256 want);
     error InsufficientAllowance(address owner, address spender, uint256 have, uint256 want);
+    error AlreadyInitialized();
+    error EnforcedPause();
+    error ExpectedPause();
+    error ExpiredSignature(uint256 deadline);
+    error InvalidSigner(address signer, address owner);
+    error ExceededCap(uint256 increased_supply, uint256 cap);
+    error ArithmeticOverflow();
+    error InvalidAccountNonce(address account, uint256 current_nonce);
+    error FutureLookup(uint256 timepoint, uint64 clock);
+    error ERC3156UnsupportedToken(address token);
+    error ERC3156ExceededMaxLoan(uint256 max_loan);
+    error ERC3156InvalidReceiver(address receiver);
+    error ERC1363InvalidReceiver(address receiver);
+    error ERC1363InvalidSpender(address spender);
+    error AccountBlocked(address account);
+    error NotAllowlisted(address account);
+    error ERC20InvalidSender(address sender);
+    error ERC20InvalidReceiver(address receiver);
+}
+
+// Interfaces of the ERC-3156 flash borrowers and of the ERC-1363 receivers and spenders
+sol! {
+    interface IERC3156FlashBorrower {
+        function onFlashLoan(address initiator, address token, uint256 amount, uint256 fee, bytes data) external returns (bytes32);
+    }
+
+    interface IERC1363Receiver {
+        function onTransferReceived(address operator, address from, uint256 value, bytes data) external returns (bytes4);
+    }
+
+    interface IERC1363Spender {
+        function onApprovalReceived(address owner, uint256 value, bytes data) external returns (bytes4);
+    }
 }
 
 /// Represents the ways methods may fail.
@@ -58,86 +181,474 @@ sol! {
 pub enum Erc20Error {
     InsufficientBalance(InsufficientBalance),
     InsufficientAllowance(InsufficientAllowance),
+    AlreadyInitialized(AlreadyInitialized),
+    EnforcedPause(EnforcedPause),
+    ExpectedPause(ExpectedPause),
+    ExpiredSignature(ExpiredSignature),
+    InvalidSigner(InvalidSigner),
+    ExceededCap(ExceededCap),
+    ArithmeticOverflow(ArithmeticOverflow),
+    InvalidAccountNonce(InvalidAccountNonce),
+    FutureLookup(FutureLookup),
+    UnsupportedToken(ERC3156UnsupportedToken),
+    ExceededMaxLoan(ERC3156ExceededMaxLoan),
+    InvalidReceiver(ERC3156InvalidReceiver),
+    InvalidTransferReceiver(ERC1363InvalidReceiver),
+    InvalidApprovalSpender(ERC1363InvalidSpender),
+    AccountBlocked(AccountBlocked),
+    NotAllowlisted(NotAllowlisted),
+    InvalidSender(ERC20InvalidSender),
+    InvalidRecipient(ERC20InvalidReceiver),
 }
 
+/// `keccak256("EIP712Domain(string name,string version,uint256 chainId,address verifyingContract)")`
+const EIP712_DOMAIN_TYPEHASH: FixedBytes<32> =
+    b256!("8b73c3c69bb8fe3d512ecc4cf759cc79239f7b179b0ffacaa9a75d522b39400f");
+
+/// `keccak256("Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)")`
+const PERMIT_TYPEHASH: FixedBytes<32> =
+    b256!("6e71edae12b1b97f4d1f60370fef10105fa2faae0126114a169c64845d6126c9");
+
+/// `keccak256("Delegation(address delegatee,uint256 nonce,uint256 expiry)")`
+const DELEGATION_TYPEHASH: FixedBytes<32> =
+    b256!("e48329057bfd03d55e49b547132e39cffd9c1820ad7b9d4c5307691425d15adf");
+
+/// `keccak256("ERC3156FlashBorrower.onFlashLoan")`, returned by borrowers accepting a flash loan
+const FLASH_LOAN_CALLBACK_SUCCESS: FixedBytes<32> =
+    b256!("439148f0bbc682ca079e46d6e2c2f0c1e3b820f1a291b069d8882abf8cf18dd9");
+
+/// `IERC1363Receiver.onTransferReceived.selector`
+const ERC1363_RECEIVER_ID: u32 = 0x88a7ca5c;
+
+/// `IERC1363Spender.onApprovalReceived.selector`
+const ERC1363_SPENDER_ID: u32 = 0x7b04a2d0;
+
+/// Version of the EIP-712 signing domain
+const EIP712_VERSION: &str = "1";
+
+/// Address of the `ecrecover` precompile
+const ECRECOVER: Address = Address::with_last_byte(1);
+
+/// Upper bound for the `s` value of a non-malleable secp256k1 signature (secp256k1n / 2)
+const SECP256K1_HALF_ORDER: U256 = U256::from_be_bytes(
+    b256!("7fffffffffffffffffffffffffffffff5d576e7357a4501ddfe92f46681b20a0").0,
+);
+
 // These methods aren't exposed to other contracts
 // Methods marked as "pub" here are usable outside of the erc20 module (i.e. they're callable from lib.rs)
 // Note: modifying storage will become much prettier soon
-impl<T: Erc20Params> Erc20<T> {
-    /// Movement of funds between 2 accounts
+impl<T: Erc20Params + Erc20Hooks> Erc20<T> {
+    /// Sets the token name and symbol, and mints `initial_supply` tokens to msg::sender()
+    /// (invoked by the external init() function of the inheriting contract, can only succeed once)
+    pub fn init(
+        &mut self,
+        name: String,
+        symbol: String,
+        initial_supply: U256,
+    ) -> Result<(), Erc20Error> {
+        if self.initialized.get() {
+            return Err(Erc20Error::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        self.initialized.set(true);
+
+        self.name.set_str(name);
+        self.symbol.set_str(symbol);
+        self.decimals.set(U8::from(T::DECIMALS));
+
+        self.mint(self.vm().msg_sender(), initial_supply)
+    }
+
+    /// Requires that the token is not paused
+    fn require_not_paused(&self) -> Result<(), Erc20Error> {
+        if self.paused.get() {
+            return Err(Erc20Error::EnforcedPause(EnforcedPause {}));
+        }
+        Ok(())
+    }
+
+    /// Halts transfers, mints and burns
+    /// (access control is left to the inheriting contract)
+    pub fn pause(&mut self) -> Result<(), Erc20Error> {
+        self.require_not_paused()?;
+        self.paused.set(true);
+        log(self.vm(), Paused { account: self.vm().msg_sender() });
+        Ok(())
+    }
+
+    /// Resumes transfers, mints and burns
+    /// (access control is left to the inheriting contract)
+    pub fn unpause(&mut self) -> Result<(), Erc20Error> {
+        if !self.paused.get() {
+            return Err(Erc20Error::ExpectedPause(ExpectedPause {}));
+        }
+        self.paused.set(false);
+        log(self.vm(), Unpaused { account: self.vm().msg_sender() });
+        Ok(())
+    }
+
+    /// Requires that neither side of a transfer is the zero address,
+    /// which [`Erc20::_update`] would treat as a mint or a burn
+    pub fn require_not_zero(from: Address, to: Address) -> Result<(), Erc20Error> {
+        if from.is_zero() {
+            return Err(Erc20Error::InvalidSender(ERC20InvalidSender { sender: from }));
+        }
+        if to.is_zero() {
+            return Err(Erc20Error::InvalidRecipient(ERC20InvalidReceiver { receiver: to }));
+        }
+        Ok(())
+    }
+
+    /// State of the [`Erc20Hooks`]
+    pub fn hook_storage(&self) -> &T::Storage {
+        &self.hooks
+    }
+
+    /// Mutable state of the [`Erc20Hooks`]
+    pub fn hook_storage_mut(&mut self) -> &mut T::Storage {
+        &mut self.hooks
+    }
+
+    /// Sets the allowance of `spender` on `owner`'s tokens
+    /// (invoked by the external approve() and permit() functions, and when an allowance is spent)
+    fn _approve(&mut self, owner: Address, spender: Address, value: U256) {
+        self.allowances.setter(owner).insert(spender, value);
+        log(self.vm(), Approval {
+            owner,
+            spender,
+            value,
+        });
+    }
+
+    /// Hashes an EIP-712 struct hash together with this token's domain separator
+    fn hash_typed_data(&self, struct_hash: FixedBytes<32>) -> FixedBytes<32> {
+        let mut digest_input = Vec::with_capacity(66);
+        digest_input.extend_from_slice(b"\x19\x01");
+        digest_input.extend_from_slice(self.domain_separator().as_slice());
+        digest_input.extend_from_slice(struct_hash.as_slice());
+        keccak(digest_input)
+    }
+
+    /// Decreases the allowance of `spender` on `owner`'s tokens by `value`,
+    /// unless it is infinite (`U256::MAX`)
+    /// (invoked by the external transfer_from() function and by inheriting contracts)
+    pub fn _spend_allowance(
+        &mut self,
+        owner: Address,
+        spender: Address,
+        value: U256,
+    ) -> Result<(), Erc20Error> {
+        let old_allowance = self.allowance(owner, spender);
+        if old_allowance == U256::MAX {
+            return Ok(());
+        }
+        if old_allowance < value {
+            return Err(Erc20Error::InsufficientAllowance(InsufficientAllowance {
+                owner,
+                spender,
+                have: old_allowance,
+                want: value,
+            }));
+        }
+        self._approve(owner, spender, old_allowance - value);
+        Ok(())
+    }
+
+    /// Recovers the signer of `hash` through the `ecrecover` precompile,
+    /// returning the zero address for invalid signatures
+    fn recover(&self, hash: FixedBytes<32>, v: u8, r: FixedBytes<32>, s: FixedBytes<32>) -> Address {
+        if U256::from_be_bytes(s.0) > SECP256K1_HALF_ORDER {
+            return Address::ZERO;
+        }
+
+        let mut input = Vec::with_capacity(128);
+        input.extend_from_slice(hash.as_slice());
+        input.extend_from_slice(&U256::from(v).to_be_bytes::<32>());
+        input.extend_from_slice(r.as_slice());
+        input.extend_from_slice(s.as_slice());
+
+        match self.vm().static_call(&Call::new(), ECRECOVER, &input) {
+            Ok(output) if output.len() == 32 => Address::from_slice(&output[12..]),
+            _ => Address::ZERO,
+        }
+    }
+
+    /// Delegates all of `delegator`'s voting power to `delegatee`
+    /// (invoked by the external delegate() and delegate_by_sig() functions)
+    fn _delegate(&mut self, delegator: Address, delegatee: Address) -> Result<(), Erc20Error> {
+        let from_delegate = self.delegates.get(delegator);
+        self.delegates.insert(delegator, delegatee);
+        log(self.vm(), DelegateChanged {
+            delegator,
+            from_delegate,
+            to_delegate: delegatee,
+        });
+
+        self.move_delegate_votes(from_delegate, delegatee, self.balances.get(delegator))
+    }
+
+    /// Moves `amount` votes from delegate `from` to delegate `to`,
+    /// recording a checkpoint for each of them
+    /// (invoked whenever tokens or delegations move)
+    fn move_delegate_votes(
+        &mut self,
+        from: Address,
+        to: Address,
+        amount: U256,
+    ) -> Result<(), Erc20Error> {
+        if from == to || amount.is_zero() {
+            return Ok(());
+        }
+        let now = self.vm().block_timestamp();
+
+        if !from.is_zero() {
+            let previous_votes = self.delegate_checkpoints.getter(from).latest();
+            let new_votes = previous_votes
+                .checked_sub(amount)
+                .ok_or(Erc20Error::ArithmeticOverflow(ArithmeticOverflow {}))?;
+            self.delegate_checkpoints.setter(from).push_checkpoint(now, new_votes);
+            log(self.vm(), DelegateVotesChanged {
+                delegate: from,
+                previous_votes,
+                new_votes,
+            });
+        }
+
+        if !to.is_zero() {
+            let previous_votes = self.delegate_checkpoints.getter(to).latest();
+            let new_votes = previous_votes
+                .checked_add(amount)
+                .ok_or(Erc20Error::ArithmeticOverflow(ArithmeticOverflow {}))?;
+            self.delegate_checkpoints.setter(to).push_checkpoint(now, new_votes);
+            log(self.vm(), DelegateVotesChanged {
+                delegate: to,
+                previous_votes,
+                new_votes,
+            });
+        }
+
+        Ok(())
+    }
+
+    /// Requires that `timepoint` is in the past, returning it as a checkpoint key
+    fn require_past(&self, timepoint: U256) -> Result<u64, Erc20Error> {
+        let clock = self.vm().block_timestamp();
+        if timepoint >= U256::from(clock) {
+            return Err(Erc20Error::FutureLookup(FutureLookup { timepoint, clock }));
+        }
+        Ok(timepoint.to())
+    }
+
+    /// Movement of funds between 2 accounts, minus the transfer fee if one applies,
+    /// returning the amount received by `to`
     /// (invoked by the external transfer() and transfer_from() functions )
     pub fn _transfer(
         &mut self,
         from: Address,
         to: Address,
         value: U256,
-    ) -> Result<(), Erc20Error> {
-        // Decreasing sender balance
-        let mut sender_balance = self.balances.setter(from);
-        let old_sender_balance = sender_balance.get();
-        if old_sender_balance < value {
-            return Err(Erc20Error::InsufficientBalance(InsufficientBalance {
+    ) -> Result<U256, Erc20Error> {
+        self.require_not_paused()?;
+        Self::require_not_zero(from, to)?;
+
+        // The fee is carved out of `value`, so the supply is left untouched
+        let (fee_recipient, fee) = T::transfer_fee(self, from, to, value).unwrap_or_default();
+        let received = value
+            .checked_sub(fee)
+            .ok_or(Erc20Error::ArithmeticOverflow(ArithmeticOverflow {}))?;
+        self._update(from, to, received)?;
+        if !fee.is_zero() {
+            // The fee is a movement of its own, so the hooks see its recipient too
+            Self::require_not_zero(from, fee_recipient)?;
+            self._update(from, fee_recipient, fee)?;
+            log(self.vm(), FeeCollected {
                 from,
-                have: old_sender_balance,
-                want: value,
-            }));
+                fee_recipient,
+                fee,
+            });
+        }
+        Ok(received)
+    }
+
+    /// Moves `value` tokens from `from` to `to`, minting when `from` is zero and burning
+    /// when `to` is zero, and runs the [`Erc20Hooks`] around it.
+    /// Every balance movement goes through here; it does not check the pause.
+    pub fn _update(&mut self, from: Address, to: Address, value: U256) -> Result<(), Erc20Error> {
+        T::before_update(self, from, to, value)?;
+
+        if from.is_zero() {
+            // Increasing total supply, within the optional cap
+            let new_supply = self
+                .total_supply
+                .get()
+                .checked_add(value)
+                .ok_or(Erc20Error::ArithmeticOverflow(ArithmeticOverflow {}))?;
+            if let Some(cap) = T::MAX_SUPPLY {
+                if new_supply > cap {
+                    return Err(Erc20Error::ExceededCap(ExceededCap {
+                        increased_supply: new_supply,
+                        cap,
+                    }));
+                }
+            }
+            self.total_supply.set(new_supply);
+            let now = self.vm().block_timestamp();
+            self.total_supply_checkpoints.push_checkpoint(now, new_supply);
+        } else {
+            // Decreasing sender balance
+            let mut sender_balance = self.balances.setter(from);
+            let old_sender_balance = sender_balance.get();
+            if old_sender_balance < value {
+                return Err(Erc20Error::InsufficientBalance(InsufficientBalance {
+                    from,
+                    have: old_sender_balance,
+                    want: value,
+                }));
+            }
+            sender_balance.set(old_sender_balance - value);
+        }
+
+        if to.is_zero() {
+            // Decreasing the total supply
+            let new_supply = self
+                .total_supply
+                .get()
+                .checked_sub(value)
+                .ok_or(Erc20Error::ArithmeticOverflow(ArithmeticOverflow {}))?;
+            self.total_supply.set(new_supply);
+            let now = self.vm().block_timestamp();
+            self.total_supply_checkpoints.push_checkpoint(now, new_supply);
+        } else {
+            // Increasing receiver balance
+            let mut to_balance = self.balances.setter(to);
+            let new_to_balance = to_balance
+                .get()
+                .checked_add(value)
+                .ok_or(Erc20Error::ArithmeticOverflow(ArithmeticOverflow {}))?;
+            to_balance.set(new_to_balance);
         }
-        sender_balance.set(old_sender_balance - value);
 
-        // Increasing receiver balance
-        let mut to_balance = self.balances.setter(to);
-        let new_to_balance = to_balance.get() + value;
-        to_balance.set(new_to_balance);
+        // Moving the voting power of the delegates
+        self.move_delegate_votes(self.delegates.get(from), self.delegates.get(to), value)?;
 
         // Emitting the transfer event
-        evm::log(Transfer { from, to, value });
-        Ok(())
+        log(self.vm(), Transfer { from, to, value });
+
+        T::after_update(self, from, to, value)
     }
 
     /// Mints `value` tokens to `address`
     pub fn mint(&mut self, address: Address, value: U256) -> Result<(), Erc20Error> {
-        // Increasing balance
-        let mut balance = self.balances.setter(address);
-        let new_balance = balance.get() + value;
-        balance.set(new_balance);
+        self.require_not_paused()?;
+        if address.is_zero() {
+            return Err(Erc20Error::InvalidRecipient(ERC20InvalidReceiver { receiver: address }));
+        }
+        self._update(Address::ZERO, address, value)
+    }
 
-        // Increasing total supply
-        self.total_supply.set(self.total_supply.get() + value);
+    /// Burns `value` tokens from `address`
+    pub fn burn(&mut self, address: Address, value: U256) -> Result<(), Erc20Error> {
+        self.require_not_paused()?;
+        if address.is_zero() {
+            return Err(Erc20Error::InvalidSender(ERC20InvalidSender { sender: address }));
+        }
+        self._update(address, Address::ZERO, value)
+    }
 
-        // Emitting the transfer event
-        evm::log(Transfer {
-            from: Address::ZERO,
-            to: address,
-            value,
+    /// Mints `amount` tokens to `to` on behalf of a bridge (ERC-7802)
+    /// (access control is left to the inheriting contract)
+    pub fn crosschain_mint(&mut self, to: Address, amount: U256) -> Result<(), Erc20Error> {
+        self.mint(to, amount)?;
+        log(self.vm(), CrosschainMint {
+            to,
+            amount,
+            sender: self.vm().msg_sender(),
         });
+        Ok(())
+    }
 
+    /// Burns `amount` tokens of `from` on behalf of a bridge (ERC-7802)
+    /// (access control is left to the inheriting contract)
+    pub fn crosschain_burn(&mut self, from: Address, amount: U256) -> Result<(), Erc20Error> {
+        self.burn(from, amount)?;
+        log(self.vm(), CrosschainBurn {
+            from,
+            amount,
+            sender: self.vm().msg_sender(),
+        });
         Ok(())
     }
 
-    /// Burns `value` tokens from `address`
-    pub fn burn(&mut self, address: Address, value: U256) -> Result<(), Erc20Error> {
-        // Decreasing balance
-        let mut balance = self.balances.setter(address);
-        let old_balance = balance.get();
-        if old_balance < value {
-            return Err(Erc20Error::InsufficientBalance(InsufficientBalance {
-                from: address,
-                have: old_balance,
-                want: value,
-            }));
-        }
-        balance.set(old_balance - value);
+    /// Calls `onTransferReceived` on the `to` address if it is a contract.
+    /// Otherwise it does nothing
+    fn call_transfer_received<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        operator: Address,
+        from: Address,
+        to: Address,
+        value: U256,
+        data: Vec<u8>,
+    ) -> Result<(), Erc20Error> {
+        let this = storage.borrow_mut();
+        if this.vm().code_size(to) > 0 {
+            let calldata = IERC1363Receiver::onTransferReceivedCall {
+                operator,
+                from,
+                value,
+                data: data.into(),
+            }
+            .abi_encode();
+            let accepted = this
+                .vm()
+                .call(&Call::new(), to, &calldata)
+                .ok()
+                .and_then(|returned| {
+                    IERC1363Receiver::onTransferReceivedCall::abi_decode_returns(&returned, true)
+                        .ok()
+                })
+                .is_some_and(|returned| u32::from_be_bytes(returned._0 .0) == ERC1363_RECEIVER_ID);
 
-        // Decreasing the total supply
-        self.total_supply.set(self.total_supply.get() - value);
+            if !accepted {
+                return Err(Erc20Error::InvalidTransferReceiver(ERC1363InvalidReceiver {
+                    receiver: to,
+                }));
+            }
+        }
+        Ok(())
+    }
 
-        // Emitting the transfer event
-        evm::log(Transfer {
-            from: address,
-            to: Address::ZERO,
-            value,
-        });
+    /// Calls `onApprovalReceived` on the `spender` address if it is a contract.
+    /// Otherwise it does nothing
+    fn call_approval_received<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        owner: Address,
+        spender: Address,
+        value: U256,
+        data: Vec<u8>,
+    ) -> Result<(), Erc20Error> {
+        let this = storage.borrow_mut();
+        if this.vm().code_size(spender) > 0 {
+            let calldata = IERC1363Spender::onApprovalReceivedCall {
+                owner,
+                value,
+                data: data.into(),
+            }
+            .abi_encode();
+            let accepted = this
+                .vm()
+                .call(&Call::new(), spender, &calldata)
+                .ok()
+                .and_then(|returned| {
+                    IERC1363Spender::onApprovalReceivedCall::abi_decode_returns(&returned, true)
+                        .ok()
+                })
+                .is_some_and(|returned| u32::from_be_bytes(returned._0 .0) == ERC1363_SPENDER_ID);
 
+            if !accepted {
+                return Err(Erc20Error::InvalidApprovalSpender(ERC1363InvalidSpender {
+                    spender,
+                }));
+            }
+        }
         Ok(())
     }
 }
@@ -145,20 +656,20 @@ impl<T: Erc20Params> Erc20<T> {
 // These methods are external to other contracts
 // Note: modifying storage will become much prettier soon
 #[public]
-impl<T: Erc20Params> Erc20<T> {
-    /// Immutable token name
-    pub fn name() -> String {
-        T::NAME.into()
+impl<T: Erc20Params + Erc20Hooks> Erc20<T> {
+    /// Token name
+    pub fn name(&self) -> String {
+        self.name.get_string()
     }
 
-    /// Immutable token symbol
-    pub fn symbol() -> String {
-        T::SYMBOL.into()
+    /// Token symbol
+    pub fn symbol(&self) -> String {
+        self.symbol.get_string()
     }
 
-    /// Immutable token decimals
-    pub fn decimals() -> u8 {
-        T::DECIMALS
+    /// Token decimals
+    pub fn decimals(&self) -> u8 {
+        self.decimals.get().to()
     }
 
     /// Total supply of tokens
@@ -166,6 +677,16 @@ impl<T: Erc20Params> Erc20<T> {
         self.total_supply.get()
     }
 
+    /// Maximum total supply of tokens (`U256::MAX` when uncapped)
+    pub fn cap() -> U256 {
+        T::MAX_SUPPLY.unwrap_or(U256::MAX)
+    }
+
+    /// Whether transfers, mints and burns are currently halted
+    pub fn paused(&self) -> bool {
+        self.paused.get()
+    }
+
     /// Balance of `address`
     pub fn balance_of(&self, owner: Address) -> U256 {
         self.balances.get(owner)
@@ -173,7 +694,7 @@ impl<T: Erc20Params> Erc20<T> {
 
     /// Transfers `value` tokens from msg::sender() to `to`
     pub fn transfer(&mut self, to: Address, value: U256) -> Result<bool, Erc20Error> {
-        self._transfer(msg::sender(), to, value)?;
+        self._transfer(self.vm().msg_sender(), to, value)?;
         Ok(true)
     }
 
@@ -185,21 +706,8 @@ impl<T: Erc20Params> Erc20<T> {
         to: Address,
         value: U256,
     ) -> Result<bool, Erc20Error> {
-        // Check msg::sender() allowance
-        let mut sender_allowances = self.allowances.setter(from);
-        let mut allowance = sender_allowances.setter(msg::sender());
-        let old_allowance = allowance.get();
-        if old_allowance < value {
-            return Err(Erc20Error::InsufficientAllowance(InsufficientAllowance {
-                owner: from,
-                spender: msg::sender(),
-                have: old_allowance,
-                want: value,
-            }));
-        }
-
-        // Decreases allowance
-        allowance.set(old_allowance - value);
+        // Check and decrease msg::sender() allowance
+        self._spend_allowance(from, self.vm().msg_sender(), value)?;
 
         // Calls the internal transfer function
         self._transfer(from, to, value)?;
@@ -209,17 +717,343 @@ impl<T: Erc20Params> Erc20<T> {
 
     /// Approves the spenditure of `value` tokens of msg::sender() to `spender`
     pub fn approve(&mut self, spender: Address, value: U256) -> bool {
-        self.allowances.setter(msg::sender()).insert(spender, value);
-        evm::log(Approval {
-            owner: msg::sender(),
-            spender,
-            value,
-        });
+        self._approve(self.vm().msg_sender(), spender, value);
         true
     }
 
+    /// Increases the allowance of msg::sender() to `spender` by `added_value`
+    /// (avoids the race of changing an allowance with approve())
+    pub fn increase_allowance(
+        &mut self,
+        spender: Address,
+        added_value: U256,
+    ) -> Result<bool, Erc20Error> {
+        let owner = self.vm().msg_sender();
+        let allowance = self
+            .allowance(owner, spender)
+            .checked_add(added_value)
+            .ok_or(Erc20Error::ArithmeticOverflow(ArithmeticOverflow {}))?;
+        self._approve(owner, spender, allowance);
+        Ok(true)
+    }
+
+    /// Decreases the allowance of msg::sender() to `spender` by `subtracted_value`
+    pub fn decrease_allowance(
+        &mut self,
+        spender: Address,
+        subtracted_value: U256,
+    ) -> Result<bool, Erc20Error> {
+        let owner = self.vm().msg_sender();
+        let current = self.allowance(owner, spender);
+        if current < subtracted_value {
+            return Err(Erc20Error::InsufficientAllowance(InsufficientAllowance {
+                owner,
+                spender,
+                have: current,
+                want: subtracted_value,
+            }));
+        }
+        self._approve(owner, spender, current - subtracted_value);
+        Ok(true)
+    }
+
+    /// Approves the spenditure of `value` tokens of `owner` to `spender`
+    /// through an EIP-2612 signature of `owner`, valid until `deadline`
+    #[allow(clippy::too_many_arguments)]
+    pub fn permit(
+        &mut self,
+        owner: Address,
+        spender: Address,
+        value: U256,
+        deadline: U256,
+        v: u8,
+        r: FixedBytes<32>,
+        s: FixedBytes<32>,
+    ) -> Result<(), Erc20Error> {
+        if U256::from(self.vm().block_timestamp()) > deadline {
+            return Err(Erc20Error::ExpiredSignature(ExpiredSignature { deadline }));
+        }
+
+        // Consumes the current nonce, so each signature can only be used once
+        let mut nonce = self.nonces.setter(owner);
+        let current_nonce = nonce.get();
+        nonce.set(current_nonce + U256::from(1));
+
+        let struct_hash = keccak(
+            (PERMIT_TYPEHASH, owner, spender, value, current_nonce, deadline).abi_encode(),
+        );
+        let digest = self.hash_typed_data(struct_hash);
+
+        let signer = self.recover(digest, v, r, s);
+        if signer.is_zero() || signer != owner {
+            return Err(Erc20Error::InvalidSigner(InvalidSigner { signer, owner }));
+        }
+
+        self._approve(owner, spender, value);
+        Ok(())
+    }
+
+    /// Returns the next `permit` / `delegateBySig` nonce of `owner`
+    pub fn nonces(&self, owner: Address) -> U256 {
+        self.nonces.get(owner)
+    }
+
+    /// Returns the EIP-712 domain separator used to sign `permit` and `delegateBySig` messages
+    #[selector(name = "DOMAIN_SEPARATOR")]
+    pub fn domain_separator(&self) -> FixedBytes<32> {
+        keccak(
+            (
+                EIP712_DOMAIN_TYPEHASH,
+                keccak(self.name.get_string().as_bytes()),
+                keccak(EIP712_VERSION.as_bytes()),
+                U256::from(self.vm().chain_id()),
+                self.vm().contract_address(),
+            )
+                .abi_encode(),
+        )
+    }
+
     /// Returns the allowance of `spender` on `owner`'s tokens
     pub fn allowance(&self, owner: Address, spender: Address) -> U256 {
         self.allowances.getter(owner).get(spender)
     }
+
+    /// Current timepoint used for vote checkpoints (the block timestamp)
+    pub fn clock(&self) -> u64 {
+        self.vm().block_timestamp()
+    }
+
+    /// Description of the clock used for vote checkpoints (ERC-6372)
+    #[selector(name = "CLOCK_MODE")]
+    pub fn clock_mode() -> String {
+        "mode=timestamp".into()
+    }
+
+    /// Returns the delegate holding `account`'s voting power
+    pub fn delegates(&self, account: Address) -> Address {
+        self.delegates.get(account)
+    }
+
+    /// Current voting power of `account`
+    pub fn get_votes(&self, account: Address) -> U256 {
+        self.delegate_checkpoints.getter(account).latest()
+    }
+
+    /// Voting power of `account` at the end of `timepoint` (must be in the past)
+    pub fn get_past_votes(&self, account: Address, timepoint: U256) -> Result<U256, Erc20Error> {
+        let key = self.require_past(timepoint)?;
+        Ok(self.delegate_checkpoints.getter(account).upper_lookup(key))
+    }
+
+    /// Total supply at the end of `timepoint` (must be in the past)
+    pub fn get_past_total_supply(&self, timepoint: U256) -> Result<U256, Erc20Error> {
+        let key = self.require_past(timepoint)?;
+        Ok(self.total_supply_checkpoints.upper_lookup(key))
+    }
+
+    /// Delegates msg::sender()'s voting power to `delegatee`
+    pub fn delegate(&mut self, delegatee: Address) -> Result<(), Erc20Error> {
+        self._delegate(self.vm().msg_sender(), delegatee)
+    }
+
+    /// Delegates the voting power of the signer to `delegatee`
+    /// through an EIP-712 signature, valid until `expiry`
+    pub fn delegate_by_sig(
+        &mut self,
+        delegatee: Address,
+        nonce: U256,
+        expiry: U256,
+        v: u8,
+        r: FixedBytes<32>,
+        s: FixedBytes<32>,
+    ) -> Result<(), Erc20Error> {
+        if U256::from(self.vm().block_timestamp()) > expiry {
+            return Err(Erc20Error::ExpiredSignature(ExpiredSignature { deadline: expiry }));
+        }
+
+        let struct_hash = keccak((DELEGATION_TYPEHASH, delegatee, nonce, expiry).abi_encode());
+        let signer = self.recover(self.hash_typed_data(struct_hash), v, r, s);
+        if signer.is_zero() {
+            return Err(Erc20Error::InvalidSigner(InvalidSigner {
+                signer,
+                owner: Address::ZERO,
+            }));
+        }
+
+        // Consumes the signer's nonce, so each signature can only be used once
+        let mut account_nonce = self.nonces.setter(signer);
+        let current_nonce = account_nonce.get();
+        if nonce != current_nonce {
+            return Err(Erc20Error::InvalidAccountNonce(InvalidAccountNonce {
+                account: signer,
+                current_nonce,
+            }));
+        }
+        account_nonce.set(current_nonce + U256::from(1));
+
+        self._delegate(signer, delegatee)
+    }
+
+    /// Maximum amount of `token` available for a flash loan
+    /// (only this token can be flash minted, up to the remaining supply cap)
+    pub fn max_flash_loan(&self, token: Address) -> U256 {
+        if !T::FLASH_LOANS || token != self.vm().contract_address() {
+            return U256::ZERO;
+        }
+        Self::cap() - self.total_supply.get()
+    }
+
+    /// Fee charged for a flash loan of `amount` of `token`
+    pub fn flash_fee(&self, token: Address, amount: U256) -> Result<U256, Erc20Error> {
+        if !T::FLASH_LOANS || token != self.vm().contract_address() {
+            return Err(Erc20Error::UnsupportedToken(ERC3156UnsupportedToken { token }));
+        }
+        amount
+            .checked_mul(U256::from(T::FLASH_FEE_BPS))
+            .map(|fee| fee / U256::from(10_000))
+            .ok_or(Erc20Error::ArithmeticOverflow(ArithmeticOverflow {}))
+    }
+
+    /// Mints `amount` tokens to `receiver`, calls its `onFlashLoan` method, and burns
+    /// the amount plus the fee back (`receiver` must approve this contract for both)
+    pub fn flash_loan<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        receiver: Address,
+        token: Address,
+        amount: U256,
+        data: Bytes,
+    ) -> Result<bool, Erc20Error> {
+        let this = storage.borrow_mut();
+        let max_loan = this.max_flash_loan(token);
+        if amount > max_loan {
+            return Err(Erc20Error::ExceededMaxLoan(ERC3156ExceededMaxLoan { max_loan }));
+        }
+        let fee = this.flash_fee(token, amount)?;
+        let initiator = this.vm().msg_sender();
+        this.mint(receiver, amount)?;
+
+        // The borrower must acknowledge the loan with the ERC-3156 magic value
+        let calldata = IERC3156FlashBorrower::onFlashLoanCall {
+            initiator,
+            token,
+            amount,
+            fee,
+            data: data.0.into(),
+        }
+        .abi_encode();
+        let acknowledged = this
+            .vm()
+            .call(&Call::new(), receiver, &calldata)
+            .ok()
+            .and_then(|returned| {
+                IERC3156FlashBorrower::onFlashLoanCall::abi_decode_returns(&returned, true).ok()
+            })
+            .is_some_and(|returned| returned._0 == FLASH_LOAN_CALLBACK_SUCCESS);
+        if !acknowledged {
+            return Err(Erc20Error::InvalidReceiver(ERC3156InvalidReceiver { receiver }));
+        }
+
+        // Pulls the loan back, together with the fee
+        let repayment = amount
+            .checked_add(fee)
+            .ok_or(Erc20Error::ArithmeticOverflow(ArithmeticOverflow {}))?;
+        let contract = this.vm().contract_address();
+        this._spend_allowance(receiver, contract, repayment)?;
+        this.burn(receiver, repayment)?;
+        Ok(true)
+    }
+
+    /// Transfers `value` tokens from msg::sender() to `to`, then calls `onTransferReceived`
+    /// on `to` if it is a contract. It includes additional data for the receiver.
+    #[selector(name = "transferAndCall")]
+    pub fn transfer_and_call_with_data<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        to: Address,
+        value: U256,
+        data: Bytes,
+    ) -> Result<bool, Erc20Error> {
+        let this = storage.borrow_mut();
+        let from = this.vm().msg_sender();
+        // The receiver is notified of the amount it got, net of any transfer fee
+        let received = this._transfer(from, to, value)?;
+        Self::call_transfer_received(storage, from, from, to, received, data.0)?;
+        Ok(true)
+    }
+
+    /// Equivalent to [`transfer_and_call_with_data`], but without the additional data.
+    #[selector(name = "transferAndCall")]
+    pub fn transfer_and_call<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        to: Address,
+        value: U256,
+    ) -> Result<bool, Erc20Error> {
+        Self::transfer_and_call_with_data(storage, to, value, Bytes(vec![]))
+    }
+
+    /// Transfers `value` tokens from `from` to `to` using msg::sender()'s allowance,
+    /// then calls `onTransferReceived` on `to` if it is a contract.
+    /// It includes additional data for the receiver.
+    #[selector(name = "transferFromAndCall")]
+    pub fn transfer_from_and_call_with_data<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        from: Address,
+        to: Address,
+        value: U256,
+        data: Bytes,
+    ) -> Result<bool, Erc20Error> {
+        let this = storage.borrow_mut();
+        let operator = this.vm().msg_sender();
+        this._spend_allowance(from, operator, value)?;
+        let received = this._transfer(from, to, value)?;
+        Self::call_transfer_received(storage, operator, from, to, received, data.0)?;
+        Ok(true)
+    }
+
+    /// Equivalent to [`transfer_from_and_call_with_data`], but without the additional data.
+    #[selector(name = "transferFromAndCall")]
+    pub fn transfer_from_and_call<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        from: Address,
+        to: Address,
+        value: U256,
+    ) -> Result<bool, Erc20Error> {
+        Self::transfer_from_and_call_with_data(storage, from, to, value, Bytes(vec![]))
+    }
+
+    /// Approves the spenditure of `value` tokens of msg::sender() to `spender`, then calls
+    /// `onApprovalReceived` on `spender` if it is a contract.
+    /// It includes additional data for the spender.
+    #[selector(name = "approveAndCall")]
+    pub fn approve_and_call_with_data<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        spender: Address,
+        value: U256,
+        data: Bytes,
+    ) -> Result<bool, Erc20Error> {
+        let this = storage.borrow_mut();
+        let owner = this.vm().msg_sender();
+        this._approve(owner, spender, value);
+        Self::call_approval_received(storage, owner, spender, value, data.0)?;
+        Ok(true)
+    }
+
+    /// Equivalent to [`approve_and_call_with_data`], but without the additional data.
+    #[selector(name = "approveAndCall")]
+    pub fn approve_and_call<S: TopLevelStorage + BorrowMut<Self>>(
+        storage: &mut S,
+        spender: Address,
+        value: U256,
+    ) -> Result<bool, Erc20Error> {
+        Self::approve_and_call_with_data(storage, spender, value, Bytes(vec![]))
+    }
+
+    /// Whether the token supports a given standard.
+    pub fn supports_interface(interface: FixedBytes<4>) -> bool {
+        const IERC165: u32 = 0x01ffc9a7;
+        const IERC20: u32 = 0x36372b07;
+        const IERC1363: u32 = 0xb0202a11;
+
+        // 0xffffffff is special cased in the ERC165 standard and never supported
+        matches!(u32::from_be_bytes(interface.0), IERC165 | IERC20 | IERC1363)
+    }
 }
\ No newline at end of file
@@ -3,53 +3,1094 @@
 extern crate alloc;
 
 // Modules and imports
-mod erc20;
+pub mod erc20;
+mod ownable;
+pub mod transfer_rules;
+pub mod votes;
 
-use alloy_primitives::{Address, U256};
-use stylus_sdk::{
-    msg,
-    prelude::*
-};
-use crate::erc20::{Erc20, Erc20Params, Erc20Error};
+use alloc::{string::String, vec::Vec};
+use alloy_primitives::{b256, Address, FixedBytes, U256};
+use stylus_sdk::prelude::*;
+use stylus_common::access_control::{AccessControl, DEFAULT_ADMIN_ROLE};
+use crate::ownable::Ownable;
+use crate::transfer_rules::{ForcedTransfer, TransferRules, MAX_TRANSFER_FEE_BPS};
+
+// Re-exported for the contracts built on the Erc20 module
+pub use crate::erc20::{Erc20, Erc20Error, Erc20Hooks, Erc20Params, NoStorage};
+
+/// `keccak256("MINTER_ROLE")`, required to mint tokens
+const MINTER_ROLE: FixedBytes<32> =
+    b256!("9f2df0fed2c77648de5860a4cc508cd0818c85b8b8a1ab4ceeef8d981c8956a6");
+
+/// `keccak256("PAUSER_ROLE")`, required to pause and unpause the token
+const PAUSER_ROLE: FixedBytes<32> =
+    b256!("65d7a28e3265b37a6474929f336521b332c1681b933f6cb9f3376673440d862a");
+
+/// `keccak256("BRIDGE_ROLE")`, held by the bridges allowed to mint and burn across chains
+const BRIDGE_ROLE: FixedBytes<32> =
+    b256!("52ba824bfabc2bcfcdf7f0edbb486ebb05e1836c90e78047efeb949990f72e5f");
 
 /// Immutable definitions
 struct RobinhoodTokenParams;
 impl Erc20Params for RobinhoodTokenParams {
-    const NAME: &'static str = "RobinhoodToken";
-    const SYMBOL: &'static str = "RH";
     const DECIMALS: u8 = 18;
 }
 
+/// Enforces the compliance lists and charges the transfer fee
+impl Erc20Hooks for RobinhoodTokenParams {
+    type Storage = TransferRules;
+
+    /// Both sides of a movement must be compliant, except the sender of a forced transfer
+    fn before_update(
+        token: &mut Erc20<Self>,
+        from: Address,
+        to: Address,
+        _value: U256,
+    ) -> Result<(), Erc20Error> {
+        let rules = token.hook_storage();
+        if !from.is_zero() && !rules.forcing() {
+            rules.require_compliant(from)?;
+        }
+        if !to.is_zero() {
+            rules.require_compliant(to)?;
+        }
+        Ok(())
+    }
+
+    fn transfer_fee(
+        token: &Erc20<Self>,
+        from: Address,
+        to: Address,
+        value: U256,
+    ) -> Option<(Address, U256)> {
+        token.hook_storage().transfer_fee(from, to, value)
+    }
+}
+
 // Define the entrypoint as a Solidity storage object. The sol_storage! macro
 // will generate Rust-equivalent structs with all fields mapped to Solidity-equivalent
 // storage slots and types.
 sol_storage! {
-    #[entrypoint]
+    #[cfg_attr(feature = "contract", entrypoint)]
     struct RobinhoodToken {
         // Allows erc20 to access RobinhoodToken's storage and make calls
         #[borrow]
         Erc20<RobinhoodTokenParams> erc20;
+        // Allows ownable to expose owner(), transfer_ownership() and renounce_ownership()
+        #[borrow]
+        Ownable ownable;
+        // Allows access_control to expose has_role(), grant_role(), revoke_role() and renounce_role()
+        #[borrow]
+        AccessControl access_control;
     }
 }
 
 #[public]
-#[inherit(Erc20<RobinhoodTokenParams>)]
+#[inherit(Erc20<RobinhoodTokenParams>, Ownable, AccessControl)]
 impl RobinhoodToken {
-    /// Mints tokens
-    pub fn mint(&mut self, value: U256) -> Result<(), Erc20Error> {
-        self.erc20.mint(msg::sender(), value)?;
+    /// Sets the token metadata, mints the initial supply to the caller, makes the caller
+    /// the owner and grants it the admin, minter and pauser roles (can only be called once)
+    pub fn init(
+        &mut self,
+        name: String,
+        symbol: String,
+        initial_supply: U256,
+    ) -> Result<(), Vec<u8>> {
+        self.erc20.init(name, symbol, initial_supply)?;
+        let owner = self.vm().msg_sender();
+        self.ownable._transfer_ownership(owner);
+        self.access_control._grant_role(DEFAULT_ADMIN_ROLE, owner);
+        self.access_control._grant_role(MINTER_ROLE, owner);
+        self.access_control._grant_role(PAUSER_ROLE, owner);
+        Ok(())
+    }
+
+    /// Transfers ownership to `new_owner`, moving the admin role along with it
+    /// (only callable by the owner)
+    pub fn transfer_ownership(&mut self, new_owner: Address) -> Result<(), Vec<u8>> {
+        let previous_owner = self.ownable.owner();
+        self.ownable.transfer_ownership(new_owner)?;
+        self.access_control._revoke_role(DEFAULT_ADMIN_ROLE, previous_owner);
+        self.access_control._grant_role(DEFAULT_ADMIN_ROLE, new_owner);
+        Ok(())
+    }
+
+    /// Leaves the token without an owner and gives up the owner's admin role
+    /// (only callable by the owner)
+    pub fn renounce_ownership(&mut self) -> Result<(), Vec<u8>> {
+        let previous_owner = self.ownable.owner();
+        self.ownable.renounce_ownership()?;
+        self.access_control._revoke_role(DEFAULT_ADMIN_ROLE, previous_owner);
+        Ok(())
+    }
+
+    /// Role required to mint tokens
+    #[selector(name = "MINTER_ROLE")]
+    pub fn minter_role() -> FixedBytes<32> {
+        MINTER_ROLE
+    }
+
+    /// Role required to pause and unpause the token
+    #[selector(name = "PAUSER_ROLE")]
+    pub fn pauser_role() -> FixedBytes<32> {
+        PAUSER_ROLE
+    }
+
+    /// Role held by the bridges allowed to mint and burn across chains
+    #[selector(name = "BRIDGE_ROLE")]
+    pub fn bridge_role() -> FixedBytes<32> {
+        BRIDGE_ROLE
+    }
+
+    /// Mints tokens (only callable by minters)
+    pub fn mint(&mut self, value: U256) -> Result<(), Vec<u8>> {
+        self.access_control.only_role(MINTER_ROLE)?;
+        self.erc20.mint(self.vm().msg_sender(), value)?;
         Ok(())
     }
 
-    /// Mints tokens to another address
-    pub fn mint_to(&mut self, to: Address, value: U256) -> Result<(), Erc20Error> {
+    /// Mints tokens to another address (only callable by minters)
+    pub fn mint_to(&mut self, to: Address, value: U256) -> Result<(), Vec<u8>> {
+        self.access_control.only_role(MINTER_ROLE)?;
         self.erc20.mint(to, value)?;
         Ok(())
     }
 
     /// Burns tokens
-    pub fn burn(&mut self, value: U256) -> Result<(), Erc20Error> {
-        self.erc20.burn(msg::sender(), value)?;
+    pub fn burn(&mut self, value: U256) -> Result<(), Vec<u8>> {
+        self.erc20.burn(self.vm().msg_sender(), value)?;
         Ok(())
     }
-}
\ No newline at end of file
+
+    /// Burns tokens of `account`, consuming msg::sender()'s allowance as transfer_from() does
+    pub fn burn_from(&mut self, account: Address, value: U256) -> Result<(), Vec<u8>> {
+        self.erc20._spend_allowance(account, self.vm().msg_sender(), value)?;
+        self.erc20.burn(account, value)?;
+        Ok(())
+    }
+
+    /// Halts transfers, mints and burns (only callable by pausers)
+    pub fn pause(&mut self) -> Result<(), Vec<u8>> {
+        self.access_control.only_role(PAUSER_ROLE)?;
+        self.erc20.pause()?;
+        Ok(())
+    }
+
+    /// Resumes transfers, mints and burns (only callable by pausers)
+    pub fn unpause(&mut self) -> Result<(), Vec<u8>> {
+        self.access_control.only_role(PAUSER_ROLE)?;
+        self.erc20.unpause()?;
+        Ok(())
+    }
+
+    /// Adds an account to or removes it from the blocklist (only callable by admins)
+    pub fn set_blocked(&mut self, account: Address, blocked: bool) -> Result<(), Vec<u8>> {
+        self.access_control.only_role(DEFAULT_ADMIN_ROLE)?;
+        self.erc20.hook_storage_mut().set_blocked(account, blocked);
+        Ok(())
+    }
+
+    /// Adds an account to or removes it from the allowlist (only callable by admins)
+    pub fn set_allowlisted(&mut self, account: Address, allowed: bool) -> Result<(), Vec<u8>> {
+        self.access_control.only_role(DEFAULT_ADMIN_ROLE)?;
+        self.erc20.hook_storage_mut().set_allowlisted(account, allowed);
+        Ok(())
+    }
+
+    /// Restricts transfers, mints and burns to allowlisted accounts (only callable by admins)
+    pub fn set_allowlist_only(&mut self, enabled: bool) -> Result<(), Vec<u8>> {
+        self.access_control.only_role(DEFAULT_ADMIN_ROLE)?;
+        self.erc20.hook_storage_mut().set_allowlist_only(enabled);
+        Ok(())
+    }
+
+    /// Moves tokens out of any account regardless of the pause and of the sender's
+    /// compliance status, e.g. to recover funds of a blocked account; the receiver
+    /// must still be compliant (only callable by admins)
+    pub fn force_transfer(
+        &mut self,
+        from: Address,
+        to: Address,
+        value: U256,
+    ) -> Result<(), Vec<u8>> {
+        self.access_control.only_role(DEFAULT_ADMIN_ROLE)?;
+        Erc20::<RobinhoodTokenParams>::require_not_zero(from, to)?;
+        self.erc20.hook_storage_mut().set_forcing(true);
+        let moved = self.erc20._update(from, to, value);
+        self.erc20.hook_storage_mut().set_forcing(false);
+        moved?;
+        log(self.vm(), ForcedTransfer { from, to, value });
+        Ok(())
+    }
+
+    /// Mints tokens bridged from another chain (ERC-7802, only callable by bridges)
+    pub fn crosschain_mint(&mut self, to: Address, amount: U256) -> Result<(), Vec<u8>> {
+        self.access_control.only_role(BRIDGE_ROLE)?;
+        self.erc20.crosschain_mint(to, amount)?;
+        Ok(())
+    }
+
+    /// Burns tokens bridged to another chain (ERC-7802, only callable by bridges)
+    pub fn crosschain_burn(&mut self, from: Address, amount: U256) -> Result<(), Vec<u8>> {
+        self.access_control.only_role(BRIDGE_ROLE)?;
+        self.erc20.crosschain_burn(from, amount)?;
+        Ok(())
+    }
+
+    /// Whether the token supports a given standard (ERC-7802 on top of the [`Erc20`] ones)
+    pub fn supports_interface(interface: FixedBytes<4>) -> bool {
+        const IERC7802: u32 = 0x33331994;
+
+        u32::from_be_bytes(interface.0) == IERC7802
+            || Erc20::<RobinhoodTokenParams>::supports_interface(interface)
+    }
+
+    /// Sets the transfer fee in basis points, up to 5% (only callable by admins)
+    pub fn set_transfer_fee(&mut self, fee_bps: U256) -> Result<(), Vec<u8>> {
+        self.access_control.only_role(DEFAULT_ADMIN_ROLE)?;
+        self.erc20.hook_storage_mut().set_transfer_fee(fee_bps)?;
+        Ok(())
+    }
+
+    /// Sets the account receiving the transfer fees (only callable by admins)
+    pub fn set_fee_recipient(&mut self, fee_recipient: Address) -> Result<(), Vec<u8>> {
+        self.access_control.only_role(DEFAULT_ADMIN_ROLE)?;
+        self.erc20.hook_storage_mut().set_fee_recipient(fee_recipient);
+        Ok(())
+    }
+
+    /// Exempts an account from transfer fees, or charges it again (only callable by admins)
+    pub fn set_fee_exempt(&mut self, account: Address, exempt: bool) -> Result<(), Vec<u8>> {
+        self.access_control.only_role(DEFAULT_ADMIN_ROLE)?;
+        self.erc20.hook_storage_mut().set_fee_exempt(account, exempt);
+        Ok(())
+    }
+
+    /// Whether an account is on the blocklist
+    pub fn is_blocked(&self, account: Address) -> bool {
+        self.erc20.hook_storage().is_blocked(account)
+    }
+
+    /// Whether an account is on the allowlist
+    pub fn is_allowlisted(&self, account: Address) -> bool {
+        self.erc20.hook_storage().is_allowlisted(account)
+    }
+
+    /// Whether only allowlisted accounts can send and receive tokens
+    pub fn allowlist_only(&self) -> bool {
+        self.erc20.hook_storage().allowlist_only()
+    }
+
+    /// Fee charged on transfers, in basis points of the transferred amount
+    pub fn transfer_fee_bps(&self) -> U256 {
+        self.erc20.hook_storage().transfer_fee_bps()
+    }
+
+    /// Hard upper bound of the transfer fee, in basis points
+    pub fn max_transfer_fee_bps() -> U256 {
+        U256::from(MAX_TRANSFER_FEE_BPS)
+    }
+
+    /// Account receiving the transfer fees
+    pub fn fee_recipient(&self) -> Address {
+        self.erc20.hook_storage().fee_recipient()
+    }
+
+    /// Whether transfers from or to an account are exempt from fees
+    pub fn is_fee_exempt(&self, account: Address) -> bool {
+        self.erc20.hook_storage().is_fee_exempt(account)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_primitives::address;
+    use stylus_sdk::testing::*;
+
+    const ALICE: Address = address!("0x000000000000000000000000000000000000a11c");
+    const BOB: Address = address!("0x0000000000000000000000000000000000000b0b");
+
+    #[test]
+    fn test_init() {
+        let vm = TestVM::default();
+        vm.set_sender(ALICE);
+        let mut contract = RobinhoodToken::from(&vm);
+
+        assert!(contract
+            .init("Cradle".into(), "CRD".into(), U256::from(1000))
+            .is_ok());
+        assert_eq!("Cradle", contract.erc20.name());
+        assert_eq!("CRD", contract.erc20.symbol());
+        assert_eq!(18, contract.erc20.decimals());
+        assert_eq!(U256::from(1000), contract.erc20.total_supply());
+        assert_eq!(U256::from(1000), contract.erc20.balance_of(ALICE));
+        assert_eq!(ALICE, contract.ownable.owner());
+        assert!(contract.access_control.has_role(DEFAULT_ADMIN_ROLE, ALICE));
+
+        // A second call must be rejected
+        assert!(contract
+            .init("Other".into(), "OTH".into(), U256::from(1))
+            .is_err());
+        assert_eq!("Cradle", contract.erc20.name());
+        assert_eq!(U256::from(1000), contract.erc20.total_supply());
+    }
+
+    #[test]
+    fn test_only_minter_mints() {
+        let vm = TestVM::default();
+        vm.set_sender(ALICE);
+        let mut contract = RobinhoodToken::from(&vm);
+        assert!(contract.init("Cradle".into(), "CRD".into(), U256::ZERO).is_ok());
+        assert!(contract.access_control.has_role(DEFAULT_ADMIN_ROLE, ALICE));
+        assert!(contract.access_control.has_role(MINTER_ROLE, ALICE));
+
+        assert!(contract.mint_to(BOB, U256::from(5)).is_ok());
+        assert_eq!(U256::from(5), contract.erc20.balance_of(BOB));
+
+        // Only admins can hand out roles
+        vm.set_sender(BOB);
+        assert!(contract.mint(U256::from(5)).is_err());
+        assert!(contract.access_control.grant_role(MINTER_ROLE, BOB).is_err());
+
+        vm.set_sender(ALICE);
+        assert!(contract.access_control.grant_role(MINTER_ROLE, BOB).is_ok());
+        assert!(contract.access_control.revoke_role(MINTER_ROLE, ALICE).is_ok());
+        assert!(contract.mint(U256::from(1)).is_err());
+
+        // Minters cannot pause, and renouncing requires confirming the caller
+        vm.set_sender(BOB);
+        assert!(contract.mint(U256::from(1)).is_ok());
+        assert!(contract.pause().is_err());
+        assert!(contract.access_control.renounce_role(MINTER_ROLE, ALICE).is_err());
+        assert!(contract.access_control.renounce_role(MINTER_ROLE, BOB).is_ok());
+        assert!(contract.mint(U256::from(1)).is_err());
+        assert_eq!(U256::from(6), contract.erc20.total_supply());
+    }
+
+    #[test]
+    fn test_role_ids() {
+        use alloy_primitives::keccak256;
+
+        assert_eq!(keccak256("MINTER_ROLE"), RobinhoodToken::minter_role());
+        assert_eq!(keccak256("PAUSER_ROLE"), RobinhoodToken::pauser_role());
+        assert_eq!(keccak256("BRIDGE_ROLE"), RobinhoodToken::bridge_role());
+    }
+
+    #[test]
+    fn test_ownership() {
+        let vm = TestVM::default();
+        vm.set_sender(ALICE);
+        let mut contract = RobinhoodToken::from(&vm);
+        assert!(contract.init("Cradle".into(), "CRD".into(), U256::ZERO).is_ok());
+
+        // Only the owner can hand the token over, and never to the zero address
+        vm.set_sender(BOB);
+        assert!(contract.transfer_ownership(BOB).is_err());
+        vm.set_sender(ALICE);
+        assert!(contract.transfer_ownership(Address::ZERO).is_err());
+
+        // The admin role follows the ownership, the other roles stay where they are
+        assert!(contract.transfer_ownership(BOB).is_ok());
+        assert_eq!(BOB, contract.ownable.owner());
+        assert!(!contract.access_control.has_role(DEFAULT_ADMIN_ROLE, ALICE));
+        assert!(contract.access_control.has_role(DEFAULT_ADMIN_ROLE, BOB));
+        assert!(contract.access_control.has_role(MINTER_ROLE, ALICE));
+        assert!(contract.set_blocked(BOB, true).is_err());
+
+        assert!(contract.renounce_ownership().is_err());
+        vm.set_sender(BOB);
+        assert!(contract.renounce_ownership().is_ok());
+        assert_eq!(Address::ZERO, contract.ownable.owner());
+        assert!(!contract.access_control.has_role(DEFAULT_ADMIN_ROLE, BOB));
+        assert!(contract.set_blocked(ALICE, true).is_err());
+    }
+
+    #[test]
+    fn test_pause() {
+        let vm = TestVM::default();
+        vm.set_sender(ALICE);
+        let mut contract = RobinhoodToken::from(&vm);
+        assert!(contract.init("Cradle".into(), "CRD".into(), U256::from(10)).is_ok());
+
+        vm.set_sender(BOB);
+        assert!(contract.pause().is_err());
+
+        vm.set_sender(ALICE);
+        assert!(contract.pause().is_ok());
+        assert!(contract.erc20.paused());
+        assert!(contract.pause().is_err());
+        assert!(matches!(
+            contract.erc20.transfer(BOB, U256::from(1)),
+            Err(Erc20Error::EnforcedPause(_))
+        ));
+        assert!(contract.mint(U256::from(1)).is_err());
+        assert!(contract.burn(U256::from(1)).is_err());
+
+        assert!(contract.unpause().is_ok());
+        assert!(!contract.erc20.paused());
+        assert!(contract.erc20.transfer(BOB, U256::from(1)).is_ok());
+        assert_eq!(U256::from(1), contract.erc20.balance_of(BOB));
+    }
+
+    #[test]
+    fn test_allowance() {
+        use crate::erc20::Approval;
+        use alloy_sol_types::SolEvent;
+
+        let vm = TestVM::default();
+        vm.set_sender(ALICE);
+        let mut contract = RobinhoodToken::from(&vm);
+        assert!(contract.init("Cradle".into(), "CRD".into(), U256::from(1000)).is_ok());
+        let approvals = || {
+            vm.get_emitted_logs()
+                .iter()
+                .filter(|(topics, _)| topics[0] == Approval::SIGNATURE_HASH)
+                .count()
+        };
+
+        // Infinite allowances are never decreased nor rewritten
+        assert!(contract.erc20.approve(BOB, U256::MAX));
+        assert_eq!(1, approvals());
+        vm.set_sender(BOB);
+        assert!(contract.erc20.transfer_from(ALICE, BOB, U256::from(100)).is_ok());
+        assert_eq!(U256::MAX, contract.erc20.allowance(ALICE, BOB));
+        assert_eq!(1, approvals());
+
+        // Finite allowances are decreased, with an Approval event
+        vm.set_sender(ALICE);
+        assert!(contract.erc20.approve(BOB, U256::from(100)));
+        vm.set_sender(BOB);
+        assert!(contract.erc20.transfer_from(ALICE, BOB, U256::from(40)).is_ok());
+        assert_eq!(U256::from(60), contract.erc20.allowance(ALICE, BOB));
+        assert_eq!(3, approvals());
+        assert!(matches!(
+            contract.erc20.transfer_from(ALICE, BOB, U256::from(61)),
+            Err(Erc20Error::InsufficientAllowance(_))
+        ));
+
+        vm.set_sender(ALICE);
+        assert!(contract.erc20.increase_allowance(BOB, U256::from(10)).is_ok());
+        assert_eq!(U256::from(70), contract.erc20.allowance(ALICE, BOB));
+        assert!(matches!(
+            contract.erc20.decrease_allowance(BOB, U256::from(71)),
+            Err(Erc20Error::InsufficientAllowance(_))
+        ));
+        assert!(contract.erc20.decrease_allowance(BOB, U256::from(70)).is_ok());
+        assert_eq!(U256::ZERO, contract.erc20.allowance(ALICE, BOB));
+
+        assert!(contract.erc20.approve(BOB, U256::MAX));
+        assert!(matches!(
+            contract.erc20.increase_allowance(BOB, U256::from(1)),
+            Err(Erc20Error::ArithmeticOverflow(_))
+        ));
+    }
+
+    #[test]
+    fn test_permit() {
+        use alloy_primitives::keccak256;
+        use alloy_sol_types::SolValue;
+        use ethers::{
+            signers::{LocalWallet, Signer},
+            types::{transaction::eip712::EIP712Domain, H256},
+        };
+
+        let wallet = LocalWallet::from_bytes(&[7u8; 32]).unwrap();
+        let owner = Address::from(wallet.address().0);
+
+        let vm = TestVM::default();
+        vm.set_block_timestamp(100);
+        vm.set_sender(ALICE);
+        let mut contract = RobinhoodToken::from(&vm);
+        assert!(contract.init("Cradle".into(), "CRD".into(), U256::ZERO).is_ok());
+
+        // The domain separator must match the one computed by off-chain signers
+        let domain = EIP712Domain {
+            name: Some("Cradle".into()),
+            version: Some("1".into()),
+            chain_id: Some(vm.chain_id().into()),
+            verifying_contract: Some(vm.contract_address().0 .0.into()),
+            salt: None,
+        };
+        assert_eq!(domain.separator(), contract.erc20.domain_separator().0);
+
+        let value = U256::from(50);
+        let deadline = U256::from(200);
+        let struct_hash = keccak256(
+            (
+                keccak256("Permit(address owner,address spender,uint256 value,uint256 nonce,uint256 deadline)"),
+                owner,
+                BOB,
+                value,
+                U256::ZERO,
+                deadline,
+            )
+                .abi_encode(),
+        );
+        let digest = keccak256([&[0x19, 0x01], &domain.separator()[..], &struct_hash[..]].concat());
+        let signature = wallet.sign_hash(H256(digest.0)).unwrap();
+        let (mut r, mut s) = ([0u8; 32], [0u8; 32]);
+        signature.r.to_big_endian(&mut r);
+        signature.s.to_big_endian(&mut s);
+        let v = signature.v as u8;
+
+        // Stand in for the ecrecover precompile
+        let recover_input = [&digest[..], &U256::from(v).to_be_bytes::<32>(), &r, &s].concat();
+        vm.mock_static_call(
+            Address::with_last_byte(1),
+            recover_input,
+            Ok(owner.into_word().to_vec()),
+        );
+
+        assert!(matches!(
+            contract.erc20.permit(owner, BOB, value, U256::from(99), v, r.into(), s.into()),
+            Err(Erc20Error::ExpiredSignature(_))
+        ));
+        assert!(contract
+            .erc20
+            .permit(owner, BOB, value, deadline, v, r.into(), s.into())
+            .is_ok());
+        assert_eq!(value, contract.erc20.allowance(owner, BOB));
+        assert_eq!(U256::from(1), contract.erc20.nonces(owner));
+
+        // The nonce has been consumed, so the same signature cannot be replayed
+        assert!(matches!(
+            contract.erc20.permit(owner, BOB, value, deadline, v, r.into(), s.into()),
+            Err(Erc20Error::InvalidSigner(_))
+        ));
+    }
+
+    #[test]
+    fn test_supply_cap() {
+        struct CappedParams;
+        impl Erc20Params for CappedParams {
+            const DECIMALS: u8 = 18;
+            const MAX_SUPPLY: Option<U256> = Some(U256::from_limbs([100, 0, 0, 0]));
+        }
+        impl Erc20Hooks for CappedParams {
+            type Storage = NoStorage;
+        }
+
+        let vm = TestVM::default();
+        let mut token = Erc20::<CappedParams>::from(&vm);
+        assert_eq!(U256::from(100), Erc20::<CappedParams>::cap());
+
+        assert!(token.mint(ALICE, U256::from(60)).is_ok());
+        assert!(matches!(
+            token.mint(BOB, U256::from(41)),
+            Err(Erc20Error::ExceededCap(_))
+        ));
+        assert!(token.mint(BOB, U256::from(40)).is_ok());
+        assert_eq!(U256::from(100), token.total_supply());
+        assert_eq!(U256::from(40), token.balance_of(BOB));
+    }
+
+    #[test]
+    fn test_mint_overflow() {
+        let vm = TestVM::default();
+        vm.set_sender(ALICE);
+        let mut contract = RobinhoodToken::from(&vm);
+        assert!(contract.init("Cradle".into(), "CRD".into(), U256::MAX).is_ok());
+        assert_eq!(U256::MAX, Erc20::<RobinhoodTokenParams>::cap());
+
+        assert!(matches!(
+            contract.erc20.mint(BOB, U256::from(1)),
+            Err(Erc20Error::ArithmeticOverflow(_))
+        ));
+        assert_eq!(U256::MAX, contract.erc20.total_supply());
+        assert_eq!(U256::ZERO, contract.erc20.balance_of(BOB));
+    }
+
+    #[test]
+    fn test_votes() {
+        let vm = TestVM::default();
+        vm.set_block_timestamp(10);
+        vm.set_sender(ALICE);
+        let mut contract = RobinhoodToken::from(&vm);
+        assert!(contract.init("Cradle".into(), "CRD".into(), U256::from(100)).is_ok());
+
+        // Balances only count as votes once delegated
+        assert_eq!(U256::ZERO, contract.erc20.get_votes(ALICE));
+        assert!(contract.erc20.delegate(ALICE).is_ok());
+        assert_eq!(ALICE, contract.erc20.delegates(ALICE));
+        assert_eq!(U256::from(100), contract.erc20.get_votes(ALICE));
+
+        vm.set_block_timestamp(20);
+        assert!(contract.erc20.transfer(BOB, U256::from(30)).is_ok());
+        assert_eq!(U256::from(70), contract.erc20.get_votes(ALICE));
+
+        vm.set_block_timestamp(30);
+        vm.set_sender(BOB);
+        assert!(contract.erc20.delegate(ALICE).is_ok());
+        assert!(contract.burn(U256::from(10)).is_ok());
+        assert_eq!(U256::from(90), contract.erc20.get_votes(ALICE));
+
+        vm.set_block_timestamp(40);
+        assert_eq!(Some(U256::from(100)), contract.erc20.get_past_votes(ALICE, U256::from(15)).ok());
+        assert_eq!(Some(U256::from(70)), contract.erc20.get_past_votes(ALICE, U256::from(25)).ok());
+        assert_eq!(Some(U256::from(90)), contract.erc20.get_past_votes(ALICE, U256::from(39)).ok());
+        assert_eq!(Some(U256::ZERO), contract.erc20.get_past_votes(ALICE, U256::from(5)).ok());
+        assert_eq!(Some(U256::from(100)), contract.erc20.get_past_total_supply(U256::from(29)).ok());
+        assert_eq!(Some(U256::from(90)), contract.erc20.get_past_total_supply(U256::from(30)).ok());
+        assert!(matches!(
+            contract.erc20.get_past_votes(ALICE, U256::from(40)),
+            Err(Erc20Error::FutureLookup(_))
+        ));
+        assert!(contract.erc20.get_past_total_supply(U256::from(40)).is_err());
+    }
+
+    #[test]
+    fn test_delegate_by_sig() {
+        use alloy_primitives::keccak256;
+        use alloy_sol_types::SolValue;
+        use ethers::{
+            signers::{LocalWallet, Signer},
+            types::H256,
+        };
+
+        let wallet = LocalWallet::from_bytes(&[7u8; 32]).unwrap();
+        let signer = Address::from(wallet.address().0);
+
+        let vm = TestVM::default();
+        vm.set_sender(ALICE);
+        let mut contract = RobinhoodToken::from(&vm);
+        assert!(contract.init("Cradle".into(), "CRD".into(), U256::ZERO).is_ok());
+        assert!(contract.mint_to(signer, U256::from(25)).is_ok());
+
+        let expiry = U256::from(1000);
+        let struct_hash = keccak256(
+            (
+                keccak256("Delegation(address delegatee,uint256 nonce,uint256 expiry)"),
+                BOB,
+                U256::ZERO,
+                expiry,
+            )
+                .abi_encode(),
+        );
+        let digest = keccak256(
+            [&[0x19, 0x01], &contract.erc20.domain_separator()[..], &struct_hash[..]].concat(),
+        );
+        let signature = wallet.sign_hash(H256(digest.0)).unwrap();
+        let (mut r, mut s) = ([0u8; 32], [0u8; 32]);
+        signature.r.to_big_endian(&mut r);
+        signature.s.to_big_endian(&mut s);
+        let v = signature.v as u8;
+
+        // Stand in for the ecrecover precompile
+        let recover_input = [&digest[..], &U256::from(v).to_be_bytes::<32>(), &r, &s].concat();
+        vm.mock_static_call(
+            Address::with_last_byte(1),
+            recover_input,
+            Ok(signer.into_word().to_vec()),
+        );
+
+        assert!(contract
+            .erc20
+            .delegate_by_sig(BOB, U256::ZERO, expiry, v, r.into(), s.into())
+            .is_ok());
+        assert_eq!(BOB, contract.erc20.delegates(signer));
+        assert_eq!(U256::from(25), contract.erc20.get_votes(BOB));
+        assert_eq!(U256::from(1), contract.erc20.nonces(signer));
+
+        // The nonce has been consumed, so the same signature cannot be replayed
+        assert!(matches!(
+            contract.erc20.delegate_by_sig(BOB, U256::ZERO, expiry, v, r.into(), s.into()),
+            Err(Erc20Error::InvalidAccountNonce(_))
+        ));
+    }
+
+    #[test]
+    fn test_flash_loan_limits() {
+        struct FlashParams;
+        impl Erc20Params for FlashParams {
+            const DECIMALS: u8 = 18;
+            const MAX_SUPPLY: Option<U256> = Some(U256::from_limbs([1_000_000, 0, 0, 0]));
+            const FLASH_FEE_BPS: u64 = 30;
+        }
+        impl Erc20Hooks for FlashParams {
+            type Storage = NoStorage;
+        }
+
+        let vm = TestVM::default();
+        let mut token = Erc20::<FlashParams>::from(&vm);
+        let this = vm.contract_address();
+        assert!(token.mint(ALICE, U256::from(400_000)).is_ok());
+
+        // Only this token can be borrowed, up to the remaining cap
+        assert_eq!(U256::from(600_000), token.max_flash_loan(this));
+        assert_eq!(U256::ZERO, token.max_flash_loan(BOB));
+        assert_eq!(
+            Some(U256::from(30)),
+            token.flash_fee(this, U256::from(10_000)).ok()
+        );
+        assert!(matches!(
+            token.flash_fee(BOB, U256::from(10_000)),
+            Err(Erc20Error::UnsupportedToken(_))
+        ));
+    }
+
+    #[test]
+    fn test_flash_loan() {
+        use crate::erc20::IERC3156FlashBorrower::onFlashLoanCall;
+        use alloy_primitives::keccak256;
+        use alloy_sol_types::{SolCall, SolValue};
+        use stylus_sdk::abi::Bytes;
+
+        type Token = Erc20<RobinhoodTokenParams>;
+        let borrower = address!("0x000000000000000000000000000000000000f1a5");
+        let vm = TestVM::default();
+        vm.set_sender(ALICE);
+        vm.set_code(borrower, vec![0xfe]);
+        let mut contract = RobinhoodToken::from(&vm);
+        assert!(contract.init("Cradle".into(), "CRD".into(), U256::from(100)).is_ok());
+        let this = vm.contract_address();
+        let amount = U256::from(1000);
+        let callback = onFlashLoanCall {
+            initiator: ALICE,
+            token: this,
+            amount,
+            fee: U256::ZERO,
+            data: b"loan".to_vec().into(),
+        }
+        .abi_encode();
+        let flash_loan = |contract: &mut RobinhoodToken| {
+            Token::flash_loan(contract, borrower, this, amount, Bytes(b"loan".to_vec()))
+        };
+
+        // The borrower must return the ERC-3156 magic value
+        vm.mock_call(borrower, callback.clone(), Ok(FixedBytes::<32>::ZERO.abi_encode()));
+        assert!(matches!(flash_loan(&mut contract), Err(Erc20Error::InvalidReceiver(_))));
+        assert!(contract.erc20.burn(borrower, amount).is_ok());
+
+        // And approve the token to pull the loan back
+        let success = keccak256("ERC3156FlashBorrower.onFlashLoan");
+        vm.mock_call(borrower, callback, Ok(success.abi_encode()));
+        assert!(matches!(
+            flash_loan(&mut contract),
+            Err(Erc20Error::InsufficientAllowance(_))
+        ));
+        assert!(contract.erc20.burn(borrower, amount).is_ok());
+
+        vm.set_sender(borrower);
+        assert!(contract.erc20.approve(this, amount));
+        vm.set_sender(ALICE);
+        assert_eq!(Some(true), flash_loan(&mut contract).ok());
+        assert_eq!(U256::ZERO, contract.erc20.balance_of(borrower));
+        assert_eq!(U256::ZERO, contract.erc20.allowance(borrower, this));
+        assert_eq!(U256::from(100), contract.erc20.total_supply());
+    }
+
+    #[test]
+    fn test_supports_interface() {
+        type Token = Erc20<RobinhoodTokenParams>;
+        assert!(Token::supports_interface(FixedBytes::new([0x01, 0xff, 0xc9, 0xa7])));
+        assert!(Token::supports_interface(FixedBytes::new([0x36, 0x37, 0x2b, 0x07])));
+        assert!(Token::supports_interface(FixedBytes::new([0xb0, 0x20, 0x2a, 0x11])));
+        assert!(!Token::supports_interface(FixedBytes::new([0xff, 0xff, 0xff, 0xff])));
+    }
+
+    #[test]
+    fn test_transfer_and_call() {
+        use crate::erc20::IERC1363Receiver::onTransferReceivedCall;
+        use crate::erc20::IERC1363Spender::onApprovalReceivedCall;
+        use alloy_sol_types::{SolCall, SolValue};
+
+        type Token = Erc20<RobinhoodTokenParams>;
+        let receiver = address!("0x00000000000000000000000000000000000ec1e5");
+        let vm = TestVM::default();
+        vm.set_sender(ALICE);
+        vm.set_code(receiver, vec![0xfe]);
+        let mut contract = RobinhoodToken::from(&vm);
+        assert!(contract.init("Cradle".into(), "CRD".into(), U256::from(100)).is_ok());
+
+        // Accounts without code are not called back
+        assert_eq!(Some(true), Token::transfer_and_call(&mut contract, BOB, U256::from(10)).ok());
+        assert_eq!(U256::from(10), contract.erc20.balance_of(BOB));
+        assert_eq!(Some(true), Token::approve_and_call(&mut contract, BOB, U256::from(5)).ok());
+        assert_eq!(U256::from(5), contract.erc20.allowance(ALICE, BOB));
+
+        // Receivers must return the onTransferReceived selector
+        let callback = |value: u64| {
+            onTransferReceivedCall {
+                operator: ALICE,
+                from: ALICE,
+                value: U256::from(value),
+                data: Default::default(),
+            }
+            .abi_encode()
+        };
+        let returned = FixedBytes::new([0xde, 0xad, 0xbe, 0xef]).abi_encode();
+        vm.mock_call(receiver, callback(20), Ok(returned));
+        assert!(matches!(
+            Token::transfer_and_call(&mut contract, receiver, U256::from(20)),
+            Err(Erc20Error::InvalidTransferReceiver(_))
+        ));
+        let returned = FixedBytes::new(onTransferReceivedCall::SELECTOR).abi_encode();
+        vm.mock_call(receiver, callback(30), Ok(returned));
+        assert_eq!(Some(true), Token::transfer_and_call(&mut contract, receiver, U256::from(30)).ok());
+
+        // With a transfer fee, receivers are told the amount they got
+        assert!(contract.mint_to(ALICE, U256::from(1_000)).is_ok());
+        assert!(contract.set_transfer_fee(U256::from(100)).is_ok());
+        assert!(contract.set_fee_recipient(BOB).is_ok());
+        let returned = FixedBytes::new(onTransferReceivedCall::SELECTOR).abi_encode();
+        vm.mock_call(receiver, callback(990), Ok(returned));
+        assert_eq!(Some(true), Token::transfer_and_call(&mut contract, receiver, U256::from(1_000)).ok());
+        assert_eq!(U256::from(20), contract.erc20.balance_of(BOB));
+
+        // Spenders must return the onApprovalReceived selector
+        let callback = onApprovalReceivedCall {
+            owner: ALICE,
+            value: U256::from(7),
+            data: Default::default(),
+        }
+        .abi_encode();
+        vm.mock_call(receiver, callback.clone(), Ok(FixedBytes::<4>::ZERO.abi_encode()));
+        assert!(matches!(
+            Token::approve_and_call(&mut contract, receiver, U256::from(7)),
+            Err(Erc20Error::InvalidApprovalSpender(_))
+        ));
+        let returned = FixedBytes::new(onApprovalReceivedCall::SELECTOR).abi_encode();
+        vm.mock_call(receiver, callback, Ok(returned));
+        assert_eq!(Some(true), Token::approve_and_call(&mut contract, receiver, U256::from(7)).ok());
+        assert_eq!(U256::from(7), contract.erc20.allowance(ALICE, receiver));
+    }
+
+    #[test]
+    fn test_crosschain() {
+        use alloy_primitives::keccak256;
+
+        // The IERC7802 interface id is the XOR of its function selectors
+        let mint = keccak256("crosschainMint(address,uint256)");
+        let burn = keccak256("crosschainBurn(address,uint256)");
+        let interface: [u8; 4] = core::array::from_fn(|i| mint[i] ^ burn[i]);
+        assert!(RobinhoodToken::supports_interface(FixedBytes::new(interface)));
+        assert!(RobinhoodToken::supports_interface(FixedBytes::new([0x36, 0x37, 0x2b, 0x07])));
+
+        let vm = TestVM::default();
+        vm.set_sender(ALICE);
+        let mut contract = RobinhoodToken::from(&vm);
+        assert!(contract.init("Cradle".into(), "CRD".into(), U256::ZERO).is_ok());
+
+        // Only bridges can mint and burn across chains, even the admin is not one by default
+        assert!(contract.crosschain_mint(ALICE, U256::from(10)).is_err());
+        assert!(contract.access_control.grant_role(BRIDGE_ROLE, BOB).is_ok());
+
+        vm.set_sender(BOB);
+        assert!(contract.crosschain_mint(ALICE, U256::from(10)).is_ok());
+        assert_eq!(U256::from(10), contract.erc20.balance_of(ALICE));
+        assert!(contract.crosschain_burn(ALICE, U256::from(4)).is_ok());
+        assert_eq!(U256::from(6), contract.erc20.balance_of(ALICE));
+        assert_eq!(U256::from(6), contract.erc20.total_supply());
+        assert!(contract.crosschain_burn(ALICE, U256::from(7)).is_err());
+    }
+
+    #[test]
+    fn test_compliance() {
+        let vm = TestVM::default();
+        vm.set_sender(ALICE);
+        let mut contract = RobinhoodToken::from(&vm);
+        assert!(contract.init("Cradle".into(), "CRD".into(), U256::from(100)).is_ok());
+        assert!(contract.erc20.transfer(BOB, U256::from(10)).is_ok());
+
+        // Blocked accounts can neither send, receive, mint nor burn
+        assert!(contract.set_blocked(BOB, true).is_ok());
+        assert!(contract.is_blocked(BOB));
+        assert!(matches!(
+            contract.erc20.transfer(BOB, U256::from(1)),
+            Err(Erc20Error::AccountBlocked(_))
+        ));
+        assert!(contract.mint_to(BOB, U256::from(1)).is_err());
+        vm.set_sender(BOB);
+        assert!(matches!(
+            contract.erc20.transfer(ALICE, U256::from(1)),
+            Err(Erc20Error::AccountBlocked(_))
+        ));
+        assert!(contract.burn(U256::from(1)).is_err());
+        assert!(contract.force_transfer(BOB, ALICE, U256::from(10)).is_err());
+
+        // The admin can still recover the blocked funds, but not send it more
+        vm.set_sender(ALICE);
+        assert!(contract.force_transfer(ALICE, BOB, U256::from(1)).is_err());
+        assert!(contract.force_transfer(BOB, ALICE, U256::from(10)).is_ok());
+        assert_eq!(U256::ZERO, contract.erc20.balance_of(BOB));
+        assert_eq!(U256::from(100), contract.erc20.balance_of(ALICE));
+        assert!(!contract.erc20.hook_storage().forcing());
+
+        // In allowlist-only mode, both sides of a transfer must be allowlisted
+        assert!(contract.set_blocked(BOB, false).is_ok());
+        assert!(contract.set_allowlist_only(true).is_ok());
+        assert!(matches!(
+            contract.erc20.transfer(BOB, U256::from(1)),
+            Err(Erc20Error::NotAllowlisted(_))
+        ));
+        assert!(contract.set_allowlisted(ALICE, true).is_ok());
+        assert!(contract.set_allowlisted(BOB, true).is_ok());
+        assert!(contract.erc20.transfer(BOB, U256::from(1)).is_ok());
+        assert!(contract.set_allowlisted(ALICE, false).is_ok());
+        assert!(contract.mint(U256::from(1)).is_err());
+        assert!(contract.set_allowlist_only(false).is_ok());
+        assert!(contract.mint(U256::from(1)).is_ok());
+    }
+
+    #[test]
+    fn test_burn_from() {
+        let vm = TestVM::default();
+        vm.set_sender(ALICE);
+        let mut contract = RobinhoodToken::from(&vm);
+        assert!(contract.init("Cradle".into(), "CRD".into(), U256::from(100)).is_ok());
+        assert!(contract.erc20.approve(BOB, U256::from(30)));
+
+        vm.set_sender(BOB);
+        assert!(contract.burn_from(ALICE, U256::from(20)).is_ok());
+        assert_eq!(U256::from(80), contract.erc20.balance_of(ALICE));
+        assert_eq!(U256::from(80), contract.erc20.total_supply());
+        assert_eq!(U256::from(10), contract.erc20.allowance(ALICE, BOB));
+
+        // Same error as transfer_from() once the allowance runs out
+        let insufficient = contract.erc20.transfer_from(ALICE, BOB, U256::from(11)).err();
+        assert!(matches!(insufficient, Some(Erc20Error::InsufficientAllowance(_))));
+        assert_eq!(
+            Some(Vec::<u8>::from(insufficient.unwrap())),
+            contract.burn_from(ALICE, U256::from(11)).err()
+        );
+        assert_eq!(U256::from(80), contract.erc20.total_supply());
+    }
+
+    #[test]
+    fn test_transfer_fee() {
+        let treasury = address!("0x0000000000000000000000000000000000007ea5");
+        let carol = address!("0x000000000000000000000000000000000000ca41");
+        let holders = [ALICE, BOB, carol, treasury];
+
+        let vm = TestVM::default();
+        vm.set_sender(ALICE);
+        let mut contract = RobinhoodToken::from(&vm);
+        assert!(contract.init("Cradle".into(), "CRD".into(), U256::from(100_000)).is_ok());
+        let sum_of_balances = |contract: &RobinhoodToken| {
+            holders
+                .iter()
+                .map(|holder| contract.erc20.balance_of(*holder))
+                .sum::<U256>()
+        };
+
+        // Only admins can configure the fee, and never above the hard cap
+        vm.set_sender(BOB);
+        assert!(contract.set_transfer_fee(U256::from(100)).is_err());
+        assert!(contract.set_fee_recipient(BOB).is_err());
+        vm.set_sender(ALICE);
+        assert!(contract.set_transfer_fee(U256::from(501)).is_err());
+        assert!(contract.set_transfer_fee(U256::from(100)).is_ok());
+
+        // Without a recipient no fee is charged
+        assert!(contract.erc20.transfer(BOB, U256::from(10_000)).is_ok());
+        assert_eq!(U256::from(10_000), contract.erc20.balance_of(BOB));
+
+        // 1% of every transfer goes to the treasury
+        assert!(contract.set_fee_recipient(treasury).is_ok());
+        vm.set_sender(BOB);
+        assert!(contract.erc20.transfer(carol, U256::from(5_000)).is_ok());
+        assert_eq!(U256::from(4_950), contract.erc20.balance_of(carol));
+        assert_eq!(U256::from(50), contract.erc20.balance_of(treasury));
+        assert_eq!(contract.erc20.total_supply(), sum_of_balances(&contract));
+
+        // Including transfer_from, which still spends the full allowance
+        vm.set_sender(carol);
+        assert!(contract.erc20.approve(BOB, U256::from(1_000)));
+        vm.set_sender(BOB);
+        assert!(contract.erc20.transfer_from(carol, ALICE, U256::from(1_000)).is_ok());
+        assert_eq!(U256::ZERO, contract.erc20.allowance(carol, BOB));
+        assert_eq!(U256::from(60), contract.erc20.balance_of(treasury));
+
+        // Exempt accounts pay no fee, whether they send or receive
+        vm.set_sender(ALICE);
+        assert!(contract.set_fee_exempt(carol, true).is_ok());
+        assert!(contract.erc20.transfer(carol, U256::from(1_000)).is_ok());
+        vm.set_sender(carol);
+        assert!(contract.erc20.transfer(BOB, U256::from(999)).is_ok());
+        assert_eq!(U256::from(60), contract.erc20.balance_of(treasury));
+
+        // Amounts too small to pay a fee are transferred in full
+        vm.set_sender(BOB);
+        assert!(contract.erc20.transfer(ALICE, U256::from(99)).is_ok());
+        assert_eq!(U256::from(60), contract.erc20.balance_of(treasury));
+
+        // The fee recipient must be compliant, like any other receiver
+        vm.set_sender(ALICE);
+        assert!(contract.set_blocked(treasury, true).is_ok());
+        vm.set_sender(BOB);
+        assert!(matches!(
+            contract.erc20.transfer(ALICE, U256::from(1_000)),
+            Err(Erc20Error::AccountBlocked(_))
+        ));
+        vm.set_sender(ALICE);
+        assert!(contract.set_blocked(treasury, false).is_ok());
+        assert_eq!(U256::from(60), contract.erc20.balance_of(treasury));
+
+        // Mints and burns are never charged
+        vm.set_sender(ALICE);
+        assert!(contract.mint_to(BOB, U256::from(1_000)).is_ok());
+        assert!(contract.burn(U256::from(1_000)).is_ok());
+        assert_eq!(U256::from(60), contract.erc20.balance_of(treasury));
+        assert_eq!(U256::from(100_000), contract.erc20.total_supply());
+        assert_eq!(contract.erc20.total_supply(), sum_of_balances(&contract));
+    }
+
+    #[test]
+    fn test_hooks() {
+        use core::sync::atomic::{AtomicUsize, Ordering};
+        static UPDATES: AtomicUsize = AtomicUsize::new(0);
+
+        // Rejects movements to BOB and counts the successful ones
+        struct HookedParams;
+        impl Erc20Params for HookedParams {
+            const DECIMALS: u8 = 18;
+        }
+        impl Erc20Hooks for HookedParams {
+            type Storage = NoStorage;
+
+            fn before_update(
+                _token: &mut Erc20<Self>,
+                _from: Address,
+                to: Address,
+                _value: U256,
+            ) -> Result<(), Erc20Error> {
+                if to == BOB {
+                    return Err(Erc20Error::AccountBlocked(crate::erc20::AccountBlocked {
+                        account: to,
+                    }));
+                }
+                Ok(())
+            }
+
+            fn after_update(
+                token: &mut Erc20<Self>,
+                _from: Address,
+                _to: Address,
+                _value: U256,
+            ) -> Result<(), Erc20Error> {
+                assert!(token.total_supply() <= U256::from(100));
+                UPDATES.fetch_add(1, Ordering::Relaxed);
+                Ok(())
+            }
+        }
+
+        let vm = TestVM::default();
+        vm.set_sender(ALICE);
+        let mut token = Erc20::<HookedParams>::from(&vm);
+        assert!(token.mint(ALICE, U256::from(100)).is_ok());
+        assert!(token.transfer(BOB, U256::from(1)).is_err());
+        assert!(token.mint(BOB, U256::from(1)).is_err());
+        let carol = address!("0x000000000000000000000000000000000000ca41");
+        assert!(token.transfer(carol, U256::from(1)).is_ok());
+        assert!(token.burn(ALICE, U256::from(9)).is_ok());
+        assert_eq!(3, UPDATES.load(Ordering::Relaxed));
+        assert_eq!(U256::from(91), token.total_supply());
+
+        // Transfers cannot be used to mint or burn
+        assert!(matches!(
+            token.transfer(Address::ZERO, U256::from(1)),
+            Err(Erc20Error::InvalidRecipient(_))
+        ));
+    }
+}
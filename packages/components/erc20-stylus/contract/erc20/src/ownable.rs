@@ -0,0 +1,89 @@
+//! Implementation of single-owner access control
+//!
+//! The [`Ownable`] type keeps track of an owner account and is intended to be
+//! inherited by contracts that need to restrict some of their methods.
+//! Use [`Ownable::only_owner`] to guard privileged entrypoints.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Imported packages
+use alloy_primitives::Address;
+use alloy_sol_types::sol;
+use stylus_sdk::prelude::*;
+
+sol_storage! {
+    /// Ownable tracks the owner of a contract.
+    pub struct Ownable {
+        /// The current owner (zero once ownership is renounced)
+        address owner;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event OwnershipTransferred(address indexed previous_owner, address indexed new_owner);
+
+    error Unauthorized(address account);
+    error InvalidOwner(address owner);
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum OwnableError {
+    Unauthorized(Unauthorized),
+    InvalidOwner(InvalidOwner),
+}
+
+// These methods aren't exposed to other contracts
+// Methods marked as "pub" here are usable outside of the ownable module (i.e. they're callable from lib.rs)
+impl Ownable {
+    /// Requires that msg::sender() is the current owner
+    pub fn only_owner(&self) -> Result<(), OwnableError> {
+        let account = self.vm().msg_sender();
+        if account != self.owner.get() {
+            return Err(OwnableError::Unauthorized(Unauthorized { account }));
+        }
+        Ok(())
+    }
+
+    /// Sets the owner without any access check
+    /// (invoked on initialization and by the external ownership functions)
+    pub fn _transfer_ownership(&mut self, new_owner: Address) {
+        let previous_owner = self.owner.get();
+        self.owner.set(new_owner);
+        log(
+            self.vm(),
+            OwnershipTransferred {
+                previous_owner,
+                new_owner,
+            },
+        );
+    }
+}
+
+// These methods are external to other contracts
+#[public]
+impl Ownable {
+    /// The current owner
+    pub fn owner(&self) -> Address {
+        self.owner.get()
+    }
+
+    /// Transfers ownership to `new_owner` (only callable by the owner)
+    pub fn transfer_ownership(&mut self, new_owner: Address) -> Result<(), OwnableError> {
+        self.only_owner()?;
+        if new_owner.is_zero() {
+            return Err(OwnableError::InvalidOwner(InvalidOwner { owner: new_owner }));
+        }
+        self._transfer_ownership(new_owner);
+        Ok(())
+    }
+
+    /// Leaves the contract without an owner, disabling all owner-only methods
+    /// (only callable by the owner)
+    pub fn renounce_ownership(&mut self) -> Result<(), OwnableError> {
+        self.only_owner()?;
+        self._transfer_ownership(Address::ZERO);
+        Ok(())
+    }
+}
@@ -0,0 +1,183 @@
+//! Implementation of the compliance and transfer fee rules of a token
+//!
+//! The [`TransferRules`] type keeps a blocklist, an optional allowlist-only mode
+//! and the transfer fee settings. It is meant to be the [`crate::Erc20Hooks::Storage`]
+//! of a token whose hooks call [`TransferRules::require_compliant`] and
+//! [`TransferRules::transfer_fee`], while its setters are guarded by the token.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Imported packages
+use alloy_primitives::{Address, U256};
+use alloy_sol_types::sol;
+use stylus_sdk::prelude::*;
+
+use crate::erc20::{AccountBlocked, Erc20Error, NotAllowlisted};
+
+/// Hard upper bound of the transfer fee, in basis points (5%)
+pub const MAX_TRANSFER_FEE_BPS: u64 = 500;
+
+sol_storage! {
+    /// TransferRules decides who can hold the token and what its transfers cost.
+    pub struct TransferRules {
+        /// Accounts that can neither send nor receive tokens
+        mapping(address => bool) blocked;
+        /// Accounts that can hold tokens while the allowlist-only mode is enabled
+        mapping(address => bool) allowlisted;
+        /// Whether only allowlisted accounts can send and receive tokens
+        bool allowlist_only;
+        /// Whether a forced transfer is under way, which skips the sender's checks
+        bool forcing;
+        /// Fee charged on transfers, in basis points of the transferred amount
+        uint256 transfer_fee_bps;
+        /// Account receiving the transfer fees
+        address fee_recipient;
+        /// Accounts whose transfers, in or out, are not charged a fee
+        mapping(address => bool) fee_exempt;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event BlocklistUpdated(address indexed account, bool blocked);
+    event AllowlistUpdated(address indexed account, bool allowed);
+    event AllowlistOnlyUpdated(bool enabled);
+    event ForcedTransfer(address indexed from, address indexed to, uint256 value);
+    event TransferFeeUpdated(uint256 fee_bps);
+    event FeeRecipientUpdated(address indexed fee_recipient);
+    event FeeExemptionUpdated(address indexed account, bool exempt);
+
+    error ExceededMaxTransferFee(uint256 fee_bps, uint256 max_fee_bps);
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum TransferRulesError {
+    ExceededMaxTransferFee(ExceededMaxTransferFee),
+}
+
+// These methods aren't exposed to other contracts
+// Methods marked as "pub" here are usable outside of the transfer_rules module (i.e. they're callable from lib.rs)
+impl TransferRules {
+    /// Requires that `account` is allowed to send or receive tokens
+    pub fn require_compliant(&self, account: Address) -> Result<(), Erc20Error> {
+        if self.blocked.get(account) {
+            return Err(Erc20Error::AccountBlocked(AccountBlocked { account }));
+        }
+        if self.allowlist_only.get() && !self.allowlisted.get(account) {
+            return Err(Erc20Error::NotAllowlisted(NotAllowlisted { account }));
+        }
+        Ok(())
+    }
+
+    /// Adds `account` to or removes it from the blocklist
+    /// (access control is left to the token)
+    pub fn set_blocked(&mut self, account: Address, blocked: bool) {
+        self.blocked.insert(account, blocked);
+        log(self.vm(), BlocklistUpdated { account, blocked });
+    }
+
+    /// Adds `account` to or removes it from the allowlist
+    /// (access control is left to the token)
+    pub fn set_allowlisted(&mut self, account: Address, allowed: bool) {
+        self.allowlisted.insert(account, allowed);
+        log(self.vm(), AllowlistUpdated { account, allowed });
+    }
+
+    /// Enables or disables the allowlist-only mode
+    /// (access control is left to the token)
+    pub fn set_allowlist_only(&mut self, enabled: bool) {
+        self.allowlist_only.set(enabled);
+        log(self.vm(), AllowlistOnlyUpdated { enabled });
+    }
+
+    /// Whether a forced transfer is under way
+    pub fn forcing(&self) -> bool {
+        self.forcing.get()
+    }
+
+    /// Marks the start or the end of a forced transfer
+    /// (invoked by the token around the movement it forces)
+    pub fn set_forcing(&mut self, forcing: bool) {
+        self.forcing.set(forcing);
+    }
+
+    /// Sets the fee charged on transfers, up to [`MAX_TRANSFER_FEE_BPS`]
+    /// (access control is left to the token)
+    pub fn set_transfer_fee(&mut self, fee_bps: U256) -> Result<(), TransferRulesError> {
+        let max_fee_bps = U256::from(MAX_TRANSFER_FEE_BPS);
+        if fee_bps > max_fee_bps {
+            return Err(TransferRulesError::ExceededMaxTransferFee(
+                ExceededMaxTransferFee {
+                    fee_bps,
+                    max_fee_bps,
+                },
+            ));
+        }
+        self.transfer_fee_bps.set(fee_bps);
+        log(self.vm(), TransferFeeUpdated { fee_bps });
+        Ok(())
+    }
+
+    /// Sets the account receiving the transfer fees (no fee is charged while it is zero)
+    /// (access control is left to the token)
+    pub fn set_fee_recipient(&mut self, fee_recipient: Address) {
+        self.fee_recipient.set(fee_recipient);
+        log(self.vm(), FeeRecipientUpdated { fee_recipient });
+    }
+
+    /// Exempts `account` from transfer fees, or charges it again
+    /// (access control is left to the token)
+    pub fn set_fee_exempt(&mut self, account: Address, exempt: bool) {
+        self.fee_exempt.insert(account, exempt);
+        log(self.vm(), FeeExemptionUpdated { account, exempt });
+    }
+
+    /// Fee charged on a transfer of `value` tokens from `from` to `to`, with its recipient
+    pub fn transfer_fee(&self, from: Address, to: Address, value: U256) -> Option<(Address, U256)> {
+        let fee_bps = self.transfer_fee_bps.get();
+        let fee_recipient = self.fee_recipient.get();
+        if fee_bps.is_zero()
+            || fee_recipient.is_zero()
+            || self.fee_exempt.get(from)
+            || self.fee_exempt.get(to)
+        {
+            return None;
+        }
+
+        // value * fee_bps / 10_000, split so the product can't overflow
+        let bps = U256::from(10_000);
+        let fee = (value / bps) * fee_bps + (value % bps) * fee_bps / bps;
+        Some((fee_recipient, fee))
+    }
+
+    /// Whether `account` is on the blocklist
+    pub fn is_blocked(&self, account: Address) -> bool {
+        self.blocked.get(account)
+    }
+
+    /// Whether `account` is on the allowlist
+    pub fn is_allowlisted(&self, account: Address) -> bool {
+        self.allowlisted.get(account)
+    }
+
+    /// Whether only allowlisted accounts can send and receive tokens
+    pub fn allowlist_only(&self) -> bool {
+        self.allowlist_only.get()
+    }
+
+    /// Fee charged on transfers, in basis points of the transferred amount
+    pub fn transfer_fee_bps(&self) -> U256 {
+        self.transfer_fee_bps.get()
+    }
+
+    /// Account receiving the transfer fees
+    pub fn fee_recipient(&self) -> Address {
+        self.fee_recipient.get()
+    }
+
+    /// Whether transfers from or to `account` are exempt from fees
+    pub fn is_fee_exempt(&self, account: Address) -> bool {
+        self.fee_exempt.get(account)
+    }
+}
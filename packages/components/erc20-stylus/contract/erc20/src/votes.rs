@@ -0,0 +1,86 @@
+//! Checkpointed value history, used for vote tracking
+//!
+//! The [`Checkpoints`] trait turns a `Checkpoint[]` storage array into a
+//! record of how a value changes over time, so that past values can be
+//! looked up by timepoint. [`Erc20`](crate::erc20::Erc20) keeps one history
+//! per delegate and one for the total supply.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Imported packages
+use alloy_primitives::{aliases::U64, U256};
+use stylus_sdk::{prelude::*, storage::StorageVec};
+
+sol_storage! {
+    /// A value recorded at a given timepoint
+    pub struct Checkpoint {
+        /// Timepoint at which the value was recorded
+        uint64 key;
+        /// The recorded value
+        uint256 value;
+    }
+}
+
+/// Ordered history of values, sorted by ascending key
+pub trait Checkpoints {
+    /// Returns the checkpoint at `pos`, or `None` if out of bounds
+    fn checkpoint_at(&self, pos: usize) -> Option<(u64, U256)>;
+
+    /// Most recent value, or zero if there are no checkpoints
+    fn latest(&self) -> U256;
+
+    /// Records `value` at timepoint `key`, overwriting the last checkpoint
+    /// when it was recorded at the same timepoint
+    fn push_checkpoint(&mut self, key: u64, value: U256);
+
+    /// Value recorded at the last checkpoint with a key lower or equal to `key`,
+    /// or zero if there is none
+    fn upper_lookup(&self, key: u64) -> U256;
+}
+
+impl Checkpoints for StorageVec<Checkpoint> {
+    fn checkpoint_at(&self, pos: usize) -> Option<(u64, U256)> {
+        self.getter(pos)
+            .map(|checkpoint| (checkpoint.key.get().to(), checkpoint.value.get()))
+    }
+
+    fn latest(&self) -> U256 {
+        match self.len() {
+            0 => U256::ZERO,
+            len => self.checkpoint_at(len - 1).map(|(_, value)| value).unwrap_or_default(),
+        }
+    }
+
+    fn push_checkpoint(&mut self, key: u64, value: U256) {
+        let len = self.len();
+        if len > 0 {
+            if let Some(mut last) = self.setter(len - 1) {
+                if last.key.get().to::<u64>() == key {
+                    last.value.set(value);
+                    return;
+                }
+            }
+        }
+
+        let mut checkpoint = self.grow();
+        checkpoint.key.set(U64::from(key));
+        checkpoint.value.set(value);
+    }
+
+    fn upper_lookup(&self, key: u64) -> U256 {
+        // Binary search for the first checkpoint strictly after `key`
+        let (mut low, mut high) = (0, self.len());
+        while low < high {
+            let mid = low + (high - low) / 2;
+            match self.checkpoint_at(mid) {
+                Some((mid_key, _)) if mid_key > key => high = mid,
+                _ => low = mid + 1,
+            }
+        }
+
+        match high {
+            0 => U256::ZERO,
+            pos => self.checkpoint_at(pos - 1).map(|(_, value)| value).unwrap_or_default(),
+        }
+    }
+}
@@ -0,0 +1,684 @@
+//! Implementation of the ERC-4626 Tokenized Vault standard
+//!
+//! [`RobinhoodVault`] holds an underlying ERC-20 asset and issues shares,
+//! which are themselves an ERC-20 token built on the shared [`Erc20`] module.
+//!
+//! Conversions always round in favour of the vault, and a virtual offset of
+//! `10^DECIMALS_OFFSET` shares and 1 asset protects early depositors against
+//! inflation (donation) attacks.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(feature = "export-abi", test)), no_main)]
+extern crate alloc;
+
+// Modules and imports
+use alloc::{string::String, vec::Vec};
+use alloy_primitives::{aliases::U8, ruint::UintTryFrom, Address, U256, U512};
+use alloy_sol_types::{sol, SolCall};
+use erc20::{Erc20, Erc20Hooks, Erc20Params, NoStorage};
+use stylus_sdk::{prelude::*, stylus_core::calls::context::Call};
+
+/// Decimals added to the asset decimals for the share token
+const DECIMALS_OFFSET: u8 = 3;
+
+/// Immutable definitions
+pub struct RobinhoodVaultParams;
+impl Erc20Params for RobinhoodVaultParams {
+    /// Unused by the vault, whose `decimals()` returns the decimals of the
+    /// asset (read in `init`) plus [`DECIMALS_OFFSET`] instead
+    const DECIMALS: u8 = 18;
+    /// Flash minted shares would not be backed by any asset
+    const FLASH_LOANS: bool = false;
+}
+impl Erc20Hooks for RobinhoodVaultParams {
+    type Storage = NoStorage;
+}
+
+// Interface of the underlying asset
+sol! {
+    interface IERC20 {
+        function decimals() external view returns (uint8);
+        function balanceOf(address account) external view returns (uint256);
+        function transfer(address to, uint256 value) external returns (bool);
+        function transferFrom(address from, address to, uint256 value) external returns (bool);
+    }
+}
+
+// Define the entrypoint as a Solidity storage object. The sol_storage! macro
+// will generate Rust-equivalent structs with all fields mapped to Solidity-equivalent
+// storage slots and types.
+sol_storage! {
+    #[entrypoint]
+    pub struct RobinhoodVault {
+        // Allows erc20 to access RobinhoodVault's storage and make calls
+        #[borrow]
+        Erc20<RobinhoodVaultParams> erc20;
+        /// The underlying asset
+        address asset;
+        /// Decimals of the underlying asset
+        uint8 asset_decimals;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event Deposit(address indexed sender, address indexed owner, uint256 assets, uint256 shares);
+    event Withdraw(address indexed sender, address indexed receiver, address indexed owner, uint256 assets, uint256 shares);
+
+    error ERC4626ExceededMaxDeposit(address receiver, uint256 assets, uint256 max);
+    error ERC4626ExceededMaxMint(address receiver, uint256 shares, uint256 max);
+    error ERC4626ExceededMaxWithdraw(address owner, uint256 assets, uint256 max);
+    error ERC4626ExceededMaxRedeem(address owner, uint256 shares, uint256 max);
+    error SafeERC20FailedOperation(address token);
+    error MathOverflow();
+    error InvalidAssetDecimals(uint8 decimals);
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum VaultError {
+    ExceededMaxDeposit(ERC4626ExceededMaxDeposit),
+    ExceededMaxMint(ERC4626ExceededMaxMint),
+    ExceededMaxWithdraw(ERC4626ExceededMaxWithdraw),
+    ExceededMaxRedeem(ERC4626ExceededMaxRedeem),
+    SafeErc20FailedOperation(SafeERC20FailedOperation),
+    MathOverflow(MathOverflow),
+    InvalidAssetDecimals(InvalidAssetDecimals),
+}
+
+/// Rounding direction of share/asset conversions
+#[derive(Clone, Copy)]
+pub enum Rounding {
+    Floor,
+    Ceil,
+}
+
+/// Computes `x * y / denominator` with a 512-bit intermediate product
+fn mul_div(x: U256, y: U256, denominator: U256, rounding: Rounding) -> Result<U256, VaultError> {
+    let product: U512 = x.widening_mul(y);
+    let denominator = U512::from(denominator);
+    let mut quotient = product / denominator;
+    if matches!(rounding, Rounding::Ceil) && !(product % denominator).is_zero() {
+        quotient += U512::from(1);
+    }
+    U256::uint_try_from(quotient).map_err(|_| VaultError::MathOverflow(MathOverflow {}))
+}
+
+/// `total_assets` plus the virtual asset backing the virtual shares
+fn virtual_assets(total_assets: U256) -> Result<U256, VaultError> {
+    total_assets
+        .checked_add(U256::from(1))
+        .ok_or(VaultError::MathOverflow(MathOverflow {}))
+}
+
+// These methods aren't exposed to other contracts
+impl RobinhoodVault {
+    /// Converts `assets` to shares, given the vault currently holds `total_assets`
+    pub fn shares_for(
+        &self,
+        assets: U256,
+        total_assets: U256,
+        rounding: Rounding,
+    ) -> Result<U256, VaultError> {
+        mul_div(assets, self.virtual_shares()?, virtual_assets(total_assets)?, rounding)
+    }
+
+    /// Converts `shares` to assets, given the vault currently holds `total_assets`
+    pub fn assets_for(
+        &self,
+        shares: U256,
+        total_assets: U256,
+        rounding: Rounding,
+    ) -> Result<U256, VaultError> {
+        mul_div(shares, virtual_assets(total_assets)?, self.virtual_shares()?, rounding)
+    }
+
+    /// Total supply of shares plus the virtual shares guarding against inflation attacks
+    fn virtual_shares(&self) -> Result<U256, VaultError> {
+        self.erc20
+            .total_supply()
+            .checked_add(U256::from(10).pow(U256::from(DECIMALS_OFFSET)))
+            .ok_or(VaultError::MathOverflow(MathOverflow {}))
+    }
+
+    /// Converts `assets` to shares at the current exchange rate
+    fn convert_to_shares_rounded(&self, assets: U256, rounding: Rounding) -> Result<U256, Vec<u8>> {
+        let total_assets = self.total_assets()?;
+        Ok(self.shares_for(assets, total_assets, rounding)?)
+    }
+
+    /// Converts `shares` to assets at the current exchange rate
+    fn convert_to_assets_rounded(&self, shares: U256, rounding: Rounding) -> Result<U256, Vec<u8>> {
+        let total_assets = self.total_assets()?;
+        Ok(self.assets_for(shares, total_assets, rounding)?)
+    }
+
+    /// Calls the asset with `call`, which must either return `true` (as read by `succeeded`)
+    /// or, like SafeERC20 allows for tokens such as USDT, return nothing at all
+    /// (the asset's revert data is bubbled up)
+    fn call_asset<C: SolCall>(
+        &mut self,
+        call: C,
+        succeeded: fn(C::Return) -> bool,
+    ) -> Result<(), Vec<u8>> {
+        let asset = self.asset.get();
+        let returned = self.vm().call(&Call::new(), asset, &call.abi_encode())?;
+        let accepted = if returned.is_empty() {
+            // An empty return value only counts as a success from a contract
+            self.vm().code_size(asset) > 0
+        } else {
+            C::abi_decode_returns(&returned, true).is_ok_and(succeeded)
+        };
+        if !accepted {
+            return Err(VaultError::SafeErc20FailedOperation(SafeERC20FailedOperation {
+                token: asset,
+            })
+            .into());
+        }
+        Ok(())
+    }
+
+    /// Pulls `assets` from the caller and mints `shares` to `receiver`
+    fn _deposit(&mut self, receiver: Address, assets: U256, shares: U256) -> Result<(), Vec<u8>> {
+        let caller = self.vm().msg_sender();
+        let this = self.vm().contract_address();
+        self.call_asset(
+            IERC20::transferFromCall {
+                from: caller,
+                to: this,
+                value: assets,
+            },
+            |returned| returned._0,
+        )?;
+
+        self.erc20.mint(receiver, shares)?;
+        log(self.vm(), Deposit {
+            sender: caller,
+            owner: receiver,
+            assets,
+            shares,
+        });
+        Ok(())
+    }
+
+    /// Burns `shares` from `owner` and sends `assets` to `receiver`
+    fn _withdraw(
+        &mut self,
+        receiver: Address,
+        owner: Address,
+        assets: U256,
+        shares: U256,
+    ) -> Result<(), Vec<u8>> {
+        let caller = self.vm().msg_sender();
+        if caller != owner {
+            self.erc20._spend_allowance(owner, caller, shares)?;
+        }
+
+        // Shares are burned before the assets leave the vault, so a reentrant
+        // call always observes a consistent exchange rate
+        self.erc20.burn(owner, shares)?;
+        self.call_asset(
+            IERC20::transferCall {
+                to: receiver,
+                value: assets,
+            },
+            |returned| returned._0,
+        )?;
+
+        log(self.vm(), Withdraw {
+            sender: caller,
+            receiver,
+            owner,
+            assets,
+            shares,
+        });
+        Ok(())
+    }
+}
+
+#[public]
+#[inherit(Erc20<RobinhoodVaultParams>)]
+impl RobinhoodVault {
+    /// Sets the underlying asset and the share token metadata (can only be called once,
+    /// and is refused for assets with more than `255 - DECIMALS_OFFSET` decimals)
+    pub fn init(&mut self, asset: Address, name: String, symbol: String) -> Result<(), Vec<u8>> {
+        self.erc20.init(name, symbol, U256::ZERO)?;
+        self.asset.set(asset);
+
+        // Assets without a `decimals()` method are assumed to use 18
+        let calldata = IERC20::decimalsCall {}.abi_encode();
+        let asset_decimals = self
+            .vm()
+            .static_call(&Call::new(), asset, &calldata)
+            .ok()
+            .and_then(|returned| IERC20::decimalsCall::abi_decode_returns(&returned, true).ok())
+            .map_or(18, |returned| returned._0);
+
+        // The share decimals (asset decimals plus the offset) must still fit in a uint8
+        if asset_decimals > u8::MAX - DECIMALS_OFFSET {
+            return Err(VaultError::InvalidAssetDecimals(InvalidAssetDecimals {
+                decimals: asset_decimals,
+            })
+            .into());
+        }
+        self.asset_decimals.set(U8::from(asset_decimals));
+        Ok(())
+    }
+
+    /// Decimals of the share token (asset decimals plus the virtual offset)
+    pub fn decimals(&self) -> u8 {
+        self.asset_decimals.get().to::<u8>() + DECIMALS_OFFSET
+    }
+
+    /// Address of the underlying asset
+    pub fn asset(&self) -> Address {
+        self.asset.get()
+    }
+
+    /// Amount of the underlying asset held by the vault
+    pub fn total_assets(&self) -> Result<U256, Vec<u8>> {
+        let asset = self.asset.get();
+        let calldata = IERC20::balanceOfCall {
+            account: self.vm().contract_address(),
+        }
+        .abi_encode();
+        let returned = self.vm().static_call(&Call::new(), asset, &calldata)?;
+        match IERC20::balanceOfCall::abi_decode_returns(&returned, true) {
+            Ok(balance) => Ok(balance._0),
+            Err(_) => Err(VaultError::SafeErc20FailedOperation(SafeERC20FailedOperation {
+                token: asset,
+            })
+            .into()),
+        }
+    }
+
+    /// Shares that `assets` are worth at the current exchange rate
+    pub fn convert_to_shares(&self, assets: U256) -> Result<U256, Vec<u8>> {
+        self.convert_to_shares_rounded(assets, Rounding::Floor)
+    }
+
+    /// Assets that `shares` are worth at the current exchange rate
+    pub fn convert_to_assets(&self, shares: U256) -> Result<U256, Vec<u8>> {
+        self.convert_to_assets_rounded(shares, Rounding::Floor)
+    }
+
+    /// Maximum assets `receiver` may deposit
+    pub fn max_deposit(&self, _receiver: Address) -> U256 {
+        U256::MAX
+    }
+
+    /// Maximum shares `receiver` may mint
+    pub fn max_mint(&self, _receiver: Address) -> U256 {
+        U256::MAX
+    }
+
+    /// Maximum assets `owner` may withdraw
+    pub fn max_withdraw(&self, owner: Address) -> Result<U256, Vec<u8>> {
+        self.convert_to_assets_rounded(self.erc20.balance_of(owner), Rounding::Floor)
+    }
+
+    /// Maximum shares `owner` may redeem
+    pub fn max_redeem(&self, owner: Address) -> U256 {
+        self.erc20.balance_of(owner)
+    }
+
+    /// Shares minted by depositing `assets` now
+    pub fn preview_deposit(&self, assets: U256) -> Result<U256, Vec<u8>> {
+        self.convert_to_shares_rounded(assets, Rounding::Floor)
+    }
+
+    /// Assets needed to mint `shares` now
+    pub fn preview_mint(&self, shares: U256) -> Result<U256, Vec<u8>> {
+        self.convert_to_assets_rounded(shares, Rounding::Ceil)
+    }
+
+    /// Shares burned by withdrawing `assets` now
+    pub fn preview_withdraw(&self, assets: U256) -> Result<U256, Vec<u8>> {
+        self.convert_to_shares_rounded(assets, Rounding::Ceil)
+    }
+
+    /// Assets returned by redeeming `shares` now
+    pub fn preview_redeem(&self, shares: U256) -> Result<U256, Vec<u8>> {
+        self.convert_to_assets_rounded(shares, Rounding::Floor)
+    }
+
+    /// Deposits `assets` from msg::sender() and mints the shares to `receiver`
+    pub fn deposit(&mut self, assets: U256, receiver: Address) -> Result<U256, Vec<u8>> {
+        let max = self.max_deposit(receiver);
+        if assets > max {
+            return Err(VaultError::ExceededMaxDeposit(ERC4626ExceededMaxDeposit {
+                receiver,
+                assets,
+                max,
+            })
+            .into());
+        }
+
+        let shares = self.preview_deposit(assets)?;
+        self._deposit(receiver, assets, shares)?;
+        Ok(shares)
+    }
+
+    /// Mints exactly `shares` to `receiver`, pulling the required assets from msg::sender()
+    pub fn mint(&mut self, shares: U256, receiver: Address) -> Result<U256, Vec<u8>> {
+        let max = self.max_mint(receiver);
+        if shares > max {
+            return Err(VaultError::ExceededMaxMint(ERC4626ExceededMaxMint {
+                receiver,
+                shares,
+                max,
+            })
+            .into());
+        }
+
+        let assets = self.preview_mint(shares)?;
+        self._deposit(receiver, assets, shares)?;
+        Ok(assets)
+    }
+
+    /// Withdraws exactly `assets` to `receiver`, burning shares from `owner`
+    /// (msg::sender() must be `owner` or have an allowance on its shares)
+    pub fn withdraw(
+        &mut self,
+        assets: U256,
+        receiver: Address,
+        owner: Address,
+    ) -> Result<U256, Vec<u8>> {
+        let max = self.max_withdraw(owner)?;
+        if assets > max {
+            return Err(VaultError::ExceededMaxWithdraw(ERC4626ExceededMaxWithdraw {
+                owner,
+                assets,
+                max,
+            })
+            .into());
+        }
+
+        let shares = self.preview_withdraw(assets)?;
+        self._withdraw(receiver, owner, assets, shares)?;
+        Ok(shares)
+    }
+
+    /// Redeems `shares` from `owner`, sending the assets to `receiver`
+    /// (msg::sender() must be `owner` or have an allowance on its shares)
+    pub fn redeem(
+        &mut self,
+        shares: U256,
+        receiver: Address,
+        owner: Address,
+    ) -> Result<U256, Vec<u8>> {
+        let max = self.max_redeem(owner);
+        if shares > max {
+            return Err(VaultError::ExceededMaxRedeem(ERC4626ExceededMaxRedeem {
+                owner,
+                shares,
+                max,
+            })
+            .into());
+        }
+
+        let assets = self.preview_redeem(shares)?;
+        self._withdraw(receiver, owner, assets, shares)?;
+        Ok(assets)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_primitives::address;
+    use alloy_sol_types::{SolError, SolEvent, SolValue};
+    use stylus_sdk::testing::*;
+
+    const ALICE: Address = address!("0x000000000000000000000000000000000000a11c");
+    const BOB: Address = address!("0x0000000000000000000000000000000000000b0b");
+    const CAROL: Address = address!("0x000000000000000000000000000000000000ca01");
+    const ASSET: Address = address!("0x00000000000000000000000000000000000a55e7");
+
+    /// Deploys a vault over a mocked 6-decimals ASSET, initialized by ALICE
+    fn deploy(vm: &TestVM) -> RobinhoodVault {
+        vm.set_sender(ALICE);
+        vm.set_code(ASSET, vec![0xfe]);
+        vm.mock_static_call(ASSET, IERC20::decimalsCall {}.abi_encode(), Ok(U256::from(6).abi_encode()));
+        let mut vault = RobinhoodVault::from(vm);
+        assert!(vault.init(ASSET, "Vault Asset".into(), "vAST".into()).is_ok());
+        vault
+    }
+
+    /// Makes ASSET report that the vault holds `total_assets`
+    fn mock_total_assets(vm: &TestVM, total_assets: u64) {
+        let account = vm.contract_address();
+        let calldata = IERC20::balanceOfCall { account }.abi_encode();
+        vm.mock_static_call(ASSET, calldata, Ok(U256::from(total_assets).abi_encode()));
+    }
+
+    /// Makes ASSET accept (or refuse) a transfer of `value` from the vault to `to`
+    fn mock_transfer(vm: &TestVM, to: Address, value: u64, accepted: bool) {
+        let value = U256::from(value);
+        let calldata = IERC20::transferCall { to, value }.abi_encode();
+        vm.mock_call(ASSET, calldata, Ok(accepted.abi_encode()));
+    }
+
+    /// Makes ASSET accept (or refuse) a transfer of `value` from `from` to the vault
+    fn mock_transfer_from(vm: &TestVM, from: Address, value: u64, accepted: bool) {
+        let to = vm.contract_address();
+        let value = U256::from(value);
+        let calldata = IERC20::transferFromCall { from, to, value }.abi_encode();
+        vm.mock_call(ASSET, calldata, Ok(accepted.abi_encode()));
+    }
+
+    /// Whether `event` was emitted
+    fn emitted<E: SolEvent>(vm: &TestVM, event: E) -> bool {
+        let log = event.encode_log_data();
+        vm.get_emitted_logs().contains(&(log.topics().to_vec(), log.data.to_vec()))
+    }
+
+    #[test]
+    fn test_init() {
+        let vm = TestVM::default();
+        let vault = deploy(&vm);
+        assert_eq!(ASSET, vault.asset());
+        assert_eq!(6 + DECIMALS_OFFSET, vault.decimals());
+        assert_eq!("vAST", vault.erc20.symbol());
+
+        // Shares can't be flash minted
+        let this = vm.contract_address();
+        assert_eq!(U256::ZERO, vault.erc20.max_flash_loan(this));
+        assert!(vault.erc20.flash_fee(this, U256::from(1)).is_err());
+    }
+
+    #[test]
+    fn test_init_rejects_oversized_decimals() {
+        let vm = TestVM::default();
+        vm.set_sender(ALICE);
+        let mut vault = RobinhoodVault::from(&vm);
+        let calldata = IERC20::decimalsCall {}.abi_encode();
+        vm.mock_static_call(ASSET, calldata.clone(), Ok(U256::from(253).abi_encode()));
+        assert_eq!(
+            Err(InvalidAssetDecimals { decimals: 253 }.abi_encode()),
+            vault.init(ASSET, "Vault Asset".into(), "vAST".into())
+        );
+
+        let vm = TestVM::default();
+        vm.set_sender(ALICE);
+        let mut vault = RobinhoodVault::from(&vm);
+        vm.mock_static_call(ASSET, calldata, Ok(U256::from(252).abi_encode()));
+        assert!(vault.init(ASSET, "Vault Asset".into(), "vAST".into()).is_ok());
+        assert_eq!(u8::MAX, vault.decimals());
+    }
+
+    #[test]
+    fn test_deposit_and_mint() {
+        let vm = TestVM::default();
+        let mut vault = deploy(&vm);
+        mock_total_assets(&vm, 0);
+
+        // The deposit is refused when the asset refuses or reverts the transfer
+        let failed = SafeERC20FailedOperation { token: ASSET }.abi_encode();
+        mock_transfer_from(&vm, ALICE, 1000, false);
+        assert_eq!(Err(failed), vault.deposit(U256::from(1000), BOB));
+        let calldata = IERC20::transferFromCall {
+            from: ALICE,
+            to: vm.contract_address(),
+            value: U256::from(1000),
+        }
+        .abi_encode();
+        vm.mock_call(ASSET, calldata.clone(), Err(b"paused".to_vec()));
+        assert_eq!(Err(b"paused".to_vec()), vault.deposit(U256::from(1000), BOB));
+        assert_eq!(U256::ZERO, vault.erc20.total_supply());
+
+        // Assets that return nothing from transferFrom (like USDT) are accepted
+        vm.mock_call(ASSET, calldata, Ok(Vec::new()));
+        assert_eq!(Ok(U256::from(1_000_000)), vault.deposit(U256::from(1000), CAROL));
+        assert_eq!(U256::from(1_000_000), vault.erc20.balance_of(CAROL));
+        assert!(vault.erc20.burn(CAROL, U256::from(1_000_000)).is_ok());
+
+        // 1000 assets are worth 10^6 shares in an empty vault
+        mock_transfer_from(&vm, ALICE, 1000, true);
+        assert_eq!(Ok(U256::from(1_000_000)), vault.deposit(U256::from(1000), BOB));
+        assert_eq!(U256::from(1_000_000), vault.erc20.balance_of(BOB));
+        assert!(emitted(&vm, Deposit {
+            sender: ALICE,
+            owner: BOB,
+            assets: U256::from(1000),
+            shares: U256::from(1_000_000),
+        }));
+
+        // Minting pulls the assets the shares are worth, rounded up
+        mock_total_assets(&vm, 1000);
+        mock_transfer_from(&vm, ALICE, 500, true);
+        assert_eq!(Ok(U256::from(500)), vault.mint(U256::from(500_000), ALICE));
+        assert_eq!(U256::from(500_000), vault.erc20.balance_of(ALICE));
+        assert!(emitted(&vm, Deposit {
+            sender: ALICE,
+            owner: ALICE,
+            assets: U256::from(500),
+            shares: U256::from(500_000),
+        }));
+    }
+
+    #[test]
+    fn test_withdraw_and_redeem() {
+        let vm = TestVM::default();
+        let mut vault = deploy(&vm);
+        assert!(vault.erc20.mint(ALICE, U256::from(1_000_000)).is_ok());
+        assert!(vault.erc20.mint(BOB, U256::from(500_000)).is_ok());
+        mock_total_assets(&vm, 1500);
+
+        // Withdrawing more than the shares are worth is refused
+        assert!(vault.withdraw(U256::from(1001), CAROL, ALICE).is_err());
+
+        // Withdrawing burns the shares the assets are worth, rounded up
+        mock_transfer(&vm, CAROL, 300, true);
+        assert_eq!(Ok(U256::from(300_000)), vault.withdraw(U256::from(300), CAROL, ALICE));
+        assert_eq!(U256::from(700_000), vault.erc20.balance_of(ALICE));
+        assert!(emitted(&vm, Withdraw {
+            sender: ALICE,
+            receiver: CAROL,
+            owner: ALICE,
+            assets: U256::from(300),
+            shares: U256::from(300_000),
+        }));
+
+        // Redeeming someone else's shares needs their allowance
+        mock_total_assets(&vm, 1200);
+        mock_transfer(&vm, CAROL, 200, true);
+        vm.set_sender(CAROL);
+        assert!(vault.redeem(U256::from(200_000), CAROL, BOB).is_err());
+        vm.set_sender(BOB);
+        assert!(vault.erc20.approve(CAROL, U256::from(200_000)));
+        vm.set_sender(CAROL);
+        assert_eq!(Ok(U256::from(200)), vault.redeem(U256::from(200_000), CAROL, BOB));
+        assert_eq!(U256::from(300_000), vault.erc20.balance_of(BOB));
+        assert_eq!(U256::ZERO, vault.erc20.allowance(BOB, CAROL));
+        assert!(emitted(&vm, Withdraw {
+            sender: CAROL,
+            receiver: CAROL,
+            owner: BOB,
+            assets: U256::from(200),
+            shares: U256::from(200_000),
+        }));
+
+        // The transfer must succeed for the redemption to go through
+        mock_total_assets(&vm, 1000);
+        mock_transfer(&vm, BOB, 300, false);
+        vm.set_sender(BOB);
+        let failed = SafeERC20FailedOperation { token: ASSET }.abi_encode();
+        assert_eq!(Err(failed), vault.redeem(U256::from(300_000), BOB, BOB));
+    }
+
+    #[test]
+    fn test_rounding_favours_vault() {
+        let vm = TestVM::default();
+        let mut vault = RobinhoodVault::from(&vm);
+        assert!(vault.erc20.mint(ALICE, U256::from(1000)).is_ok());
+
+        // 1000 shares (+ 1000 virtual) backed by 1 asset (+ 1 virtual)
+        let total_assets = U256::from(1);
+        let floor = vault.shares_for(U256::from(3), total_assets, Rounding::Floor).ok();
+        let ceil = vault.shares_for(U256::from(3), total_assets, Rounding::Ceil).ok();
+        assert_eq!(Some(U256::from(3000)), floor);
+        assert_eq!(Some(U256::from(3000)), ceil);
+
+        let floor = vault.assets_for(U256::from(1999), total_assets, Rounding::Floor).ok();
+        let ceil = vault.assets_for(U256::from(1999), total_assets, Rounding::Ceil).ok();
+        assert_eq!(Some(U256::from(1)), floor);
+        assert_eq!(Some(U256::from(2)), ceil);
+    }
+
+    #[test]
+    fn test_inflation_attack_is_unprofitable() {
+        let vm = TestVM::default();
+        let mut vault = RobinhoodVault::from(&vm);
+
+        // The attacker deposits 1 asset, then donates a large amount to the vault
+        let attacker_shares = vault
+            .shares_for(U256::from(1), U256::ZERO, Rounding::Floor)
+            .unwrap_or_default();
+        assert!(vault.erc20.mint(ALICE, attacker_shares).is_ok());
+        let total_assets = U256::from(1_000_001);
+
+        // The victim still receives shares for a deposit smaller than the donation
+        let victim_shares = vault
+            .shares_for(U256::from(500_000), total_assets, Rounding::Floor)
+            .unwrap_or_default();
+        assert!(victim_shares > U256::ZERO);
+        assert!(vault.erc20.mint(BOB, victim_shares).is_ok());
+
+        // And the attacker cannot redeem more than they put in
+        let attacker_assets = vault
+            .assets_for(attacker_shares, total_assets + U256::from(500_000), Rounding::Floor)
+            .unwrap_or_default();
+        assert!(attacker_assets < U256::from(1_000_001));
+    }
+
+    #[test]
+    fn test_mul_div_overflow() {
+        assert!(matches!(
+            mul_div(U256::MAX, U256::from(2), U256::from(1), Rounding::Floor),
+            Err(VaultError::MathOverflow(_))
+        ));
+        assert_eq!(
+            Some(U256::MAX),
+            mul_div(U256::MAX, U256::MAX, U256::MAX, Rounding::Ceil).ok()
+        );
+    }
+
+    #[test]
+    fn test_total_assets_overflow() {
+        let vm = TestVM::default();
+        let vault = RobinhoodVault::from(&vm);
+
+        // An asset balance of U256::MAX leaves no room for the virtual asset
+        assert!(matches!(
+            vault.shares_for(U256::from(1), U256::MAX, Rounding::Floor),
+            Err(VaultError::MathOverflow(_))
+        ));
+        assert!(matches!(
+            vault.assets_for(U256::from(1), U256::MAX, Rounding::Floor),
+            Err(VaultError::MathOverflow(_))
+        ));
+    }
+}
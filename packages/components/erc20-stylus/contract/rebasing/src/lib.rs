@@ -0,0 +1,490 @@
+//! Implementation of a shares-based rebasing ERC-20 token
+//!
+//! [`RebasingToken`] stores shares rather than balances: each account owns a
+//! fraction of the pool, and its balance is derived as
+//! `shares * total_pooled / total_shares`. When an oracle reports a new pool
+//! total through `rebase`, every balance grows (or shrinks) at once, without
+//! any transfer, like stETH.
+//!
+//! Token amounts are converted to shares rounding down, so a transfer may
+//! move 1-2 wei less than requested, and amounts worth less than one share
+//! are refused. `Transfer` events are emitted in token
+//! units so that existing ERC-20 tooling works, alongside `TransferShares`.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(feature = "export-abi", test)), no_main)]
+extern crate alloc;
+
+// Modules and imports
+use alloc::{string::String, vec::Vec};
+use alloy_primitives::{b256, ruint::UintTryFrom, Address, FixedBytes, U256, U512};
+use alloy_sol_types::sol;
+use stylus_sdk::prelude::*;
+use stylus_common::access_control::{AccessControl, DEFAULT_ADMIN_ROLE};
+
+/// `keccak256("ORACLE_ROLE")`, required to report the pool total
+const ORACLE_ROLE: FixedBytes<32> =
+    b256!("68e79a7bf1e0bc45d0a330c573bc367f9cf464fd326078812f301165fbda4ef1");
+
+/// `keccak256("MINTER_ROLE")`, required to mint tokens
+const MINTER_ROLE: FixedBytes<32> =
+    b256!("9f2df0fed2c77648de5860a4cc508cd0818c85b8b8a1ab4ceeef8d981c8956a6");
+
+/// Token decimals
+const DECIMALS: u8 = 18;
+
+// Define the entrypoint as a Solidity storage object. The sol_storage! macro
+// will generate Rust-equivalent structs with all fields mapped to Solidity-equivalent
+// storage slots and types.
+sol_storage! {
+    #[entrypoint]
+    pub struct RebasingToken {
+        // Allows access_control to expose has_role(), grant_role(), revoke_role() and renounce_role()
+        #[borrow]
+        AccessControl access_control;
+        /// Maps users to their shares of the pool
+        mapping(address => uint256) shares;
+        /// Maps users to a mapping of each spender's allowance, in token units
+        mapping(address => mapping(address => uint256)) allowances;
+        /// Sum of all shares
+        uint256 total_shares;
+        /// Amount of tokens backing all shares, as reported by the oracle
+        uint256 total_pooled;
+        /// Token name
+        string name;
+        /// Token symbol
+        string symbol;
+        /// Whether `init` has already been called
+        bool initialized;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event Transfer(address indexed from, address indexed to, uint256 value);
+    event TransferShares(address indexed from, address indexed to, uint256 shares);
+    event Approval(address indexed owner, address indexed spender, uint256 value);
+    event TokenRebased(uint256 previous_total, uint256 new_total, uint256 total_shares);
+
+    error InsufficientShares(address from, uint256 have, uint256 want);
+    error InsufficientAllowance(address owner, address spender, uint256 have, uint256 want);
+    error InvalidReceiver(address receiver);
+    error InvalidRebase(uint256 new_total);
+    error ZeroShares(uint256 value);
+    error AlreadyInitialized();
+    error MathOverflow();
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum RebasingError {
+    InsufficientShares(InsufficientShares),
+    InsufficientAllowance(InsufficientAllowance),
+    InvalidReceiver(InvalidReceiver),
+    InvalidRebase(InvalidRebase),
+    ZeroShares(ZeroShares),
+    AlreadyInitialized(AlreadyInitialized),
+    MathOverflow(MathOverflow),
+}
+
+/// Computes `x * y / denominator`, rounding down, with a 512-bit intermediate product
+fn mul_div(x: U256, y: U256, denominator: U256) -> Result<U256, RebasingError> {
+    let quotient = x.widening_mul::<256, 4, 512, 8>(y) / U512::from(denominator);
+    U256::uint_try_from(quotient).map_err(|_| RebasingError::MathOverflow(MathOverflow {}))
+}
+
+// These methods aren't exposed to other contracts
+impl RebasingToken {
+    /// Moves `shares` from `from` to `to`, emitting both events
+    /// (`value` is their worth in tokens, used for the `Transfer` event)
+    fn _transfer_shares(
+        &mut self,
+        from: Address,
+        to: Address,
+        shares: U256,
+        value: U256,
+    ) -> Result<(), RebasingError> {
+        if to.is_zero() {
+            return Err(RebasingError::InvalidReceiver(InvalidReceiver { receiver: to }));
+        }
+        if shares.is_zero() && !value.is_zero() {
+            return Err(RebasingError::ZeroShares(ZeroShares { value }));
+        }
+
+        let from_shares = self.shares.get(from);
+        if from_shares < shares {
+            return Err(RebasingError::InsufficientShares(InsufficientShares {
+                from,
+                have: from_shares,
+                want: shares,
+            }));
+        }
+        self.shares.insert(from, from_shares - shares);
+        let to_shares = self.shares.get(to);
+        self.shares.insert(to, to_shares + shares);
+
+        log(self.vm(), Transfer { from, to, value });
+        log(self.vm(), TransferShares { from, to, shares });
+        Ok(())
+    }
+
+    /// Issues new shares worth `value` tokens to `to`, growing the pool by `value`
+    fn _mint(&mut self, to: Address, value: U256) -> Result<(), RebasingError> {
+        if to.is_zero() {
+            return Err(RebasingError::InvalidReceiver(InvalidReceiver { receiver: to }));
+        }
+
+        let shares = self.get_shares_for_pooled(value)?;
+        if shares.is_zero() && !value.is_zero() {
+            return Err(RebasingError::ZeroShares(ZeroShares { value }));
+        }
+        let overflow = || RebasingError::MathOverflow(MathOverflow {});
+        let total_shares = self.total_shares.get().checked_add(shares).ok_or_else(overflow)?;
+        let total_pooled = self.total_pooled.get().checked_add(value).ok_or_else(overflow)?;
+        self.total_shares.set(total_shares);
+        self.total_pooled.set(total_pooled);
+        let to_shares = self.shares.get(to);
+        self.shares.insert(to, to_shares + shares);
+
+        log(self.vm(), Transfer {
+            from: Address::ZERO,
+            to,
+            value,
+        });
+        log(self.vm(), TransferShares {
+            from: Address::ZERO,
+            to,
+            shares,
+        });
+        Ok(())
+    }
+
+    /// Sets the allowance of `spender` on `owner`'s tokens
+    fn _approve(&mut self, owner: Address, spender: Address, value: U256) {
+        self.allowances.setter(owner).insert(spender, value);
+        log(self.vm(), Approval {
+            owner,
+            spender,
+            value,
+        });
+    }
+
+    /// Decreases the allowance of `spender` on `owner`'s tokens by `value`,
+    /// unless it is infinite (`U256::MAX`)
+    fn _spend_allowance(
+        &mut self,
+        owner: Address,
+        spender: Address,
+        value: U256,
+    ) -> Result<(), RebasingError> {
+        let allowance = self.allowance(owner, spender);
+        if allowance == U256::MAX {
+            return Ok(());
+        }
+        if allowance < value {
+            return Err(RebasingError::InsufficientAllowance(InsufficientAllowance {
+                owner,
+                spender,
+                have: allowance,
+                want: value,
+            }));
+        }
+        self._approve(owner, spender, allowance - value);
+        Ok(())
+    }
+}
+
+// These methods are external to other contracts
+#[public]
+#[inherit(AccessControl)]
+impl RebasingToken {
+    /// Sets the token metadata, mints `initial_supply` tokens (and as many shares) to the
+    /// caller and grants it the admin, oracle and minter roles (can only be called once)
+    pub fn init(
+        &mut self,
+        name: String,
+        symbol: String,
+        initial_supply: U256,
+    ) -> Result<(), Vec<u8>> {
+        if self.initialized.get() {
+            return Err(RebasingError::AlreadyInitialized(AlreadyInitialized {}).into());
+        }
+        self.initialized.set(true);
+        self.name.set_str(name);
+        self.symbol.set_str(symbol);
+
+        let owner = self.vm().msg_sender();
+        self.access_control._grant_role(DEFAULT_ADMIN_ROLE, owner);
+        self.access_control._grant_role(ORACLE_ROLE, owner);
+        self.access_control._grant_role(MINTER_ROLE, owner);
+        self._mint(owner, initial_supply)?;
+        Ok(())
+    }
+
+    /// Role required to report the pool total
+    #[selector(name = "ORACLE_ROLE")]
+    pub fn oracle_role() -> FixedBytes<32> {
+        ORACLE_ROLE
+    }
+
+    /// Role required to mint tokens
+    #[selector(name = "MINTER_ROLE")]
+    pub fn minter_role() -> FixedBytes<32> {
+        MINTER_ROLE
+    }
+
+    /// Token name
+    pub fn name(&self) -> String {
+        self.name.get_string()
+    }
+
+    /// Token symbol
+    pub fn symbol(&self) -> String {
+        self.symbol.get_string()
+    }
+
+    /// Token decimals
+    pub fn decimals(&self) -> u8 {
+        DECIMALS
+    }
+
+    /// Total supply of tokens, i.e. the pool total
+    pub fn total_supply(&self) -> U256 {
+        self.total_pooled.get()
+    }
+
+    /// Sum of all shares
+    pub fn total_shares(&self) -> U256 {
+        self.total_shares.get()
+    }
+
+    /// Shares of the pool owned by `account`
+    pub fn shares_of(&self, account: Address) -> U256 {
+        self.shares.get(account)
+    }
+
+    /// Tokens that `shares` are worth at the current pool total
+    pub fn get_pooled_for_shares(&self, shares: U256) -> Result<U256, RebasingError> {
+        let total_shares = self.total_shares.get();
+        if total_shares.is_zero() {
+            return Ok(shares);
+        }
+        mul_div(shares, self.total_pooled.get(), total_shares)
+    }
+
+    /// Shares that `value` tokens are worth at the current pool total
+    pub fn get_shares_for_pooled(&self, value: U256) -> Result<U256, RebasingError> {
+        let total_pooled = self.total_pooled.get();
+        if total_pooled.is_zero() {
+            return Ok(value);
+        }
+        mul_div(value, self.total_shares.get(), total_pooled)
+    }
+
+    /// Balance of `account`, derived from its shares
+    pub fn balance_of(&self, account: Address) -> U256 {
+        // Can't overflow, as shares <= total_shares
+        self.get_pooled_for_shares(self.shares_of(account)).unwrap_or_default()
+    }
+
+    /// Transfers the shares worth `value` tokens from msg::sender() to `to`
+    pub fn transfer(&mut self, to: Address, value: U256) -> Result<bool, RebasingError> {
+        let shares = self.get_shares_for_pooled(value)?;
+        self._transfer_shares(self.vm().msg_sender(), to, shares, value)?;
+        Ok(true)
+    }
+
+    /// Transfers the shares worth `value` tokens from `from` to `to`
+    /// (msg::sender() must be able to spend at least `value` tokens from `from`)
+    pub fn transfer_from(
+        &mut self,
+        from: Address,
+        to: Address,
+        value: U256,
+    ) -> Result<bool, RebasingError> {
+        self._spend_allowance(from, self.vm().msg_sender(), value)?;
+        let shares = self.get_shares_for_pooled(value)?;
+        self._transfer_shares(from, to, shares, value)?;
+        Ok(true)
+    }
+
+    /// Transfers `shares` from msg::sender() to `to`, returning the tokens they are worth
+    pub fn transfer_shares(&mut self, to: Address, shares: U256) -> Result<U256, RebasingError> {
+        let value = self.get_pooled_for_shares(shares)?;
+        self._transfer_shares(self.vm().msg_sender(), to, shares, value)?;
+        Ok(value)
+    }
+
+    /// Approves the spenditure of `value` tokens of msg::sender() to `spender`
+    pub fn approve(&mut self, spender: Address, value: U256) -> bool {
+        self._approve(self.vm().msg_sender(), spender, value);
+        true
+    }
+
+    /// Returns the allowance of `spender` on `owner`'s tokens
+    pub fn allowance(&self, owner: Address, spender: Address) -> U256 {
+        self.allowances.getter(owner).get(spender)
+    }
+
+    /// Mints `value` tokens to `to`, as new shares at the current pool total
+    /// (only callable by minters)
+    pub fn mint(&mut self, to: Address, value: U256) -> Result<(), Vec<u8>> {
+        self.access_control.only_role(MINTER_ROLE)?;
+        self._mint(to, value)?;
+        Ok(())
+    }
+
+    /// Sets the pool total, scaling every balance at once (only callable by oracles)
+    pub fn rebase(&mut self, new_total: U256) -> Result<(), Vec<u8>> {
+        self.access_control.only_role(ORACLE_ROLE)?;
+
+        // Shares must keep some value, and a pool without shares can't be given any,
+        // or new shares could not be priced
+        let total_shares = self.total_shares.get();
+        if new_total.is_zero() != total_shares.is_zero() {
+            return Err(RebasingError::InvalidRebase(InvalidRebase { new_total }).into());
+        }
+
+        let previous_total = self.total_pooled.get();
+        self.total_pooled.set(new_total);
+        log(self.vm(), TokenRebased {
+            previous_total,
+            new_total,
+            total_shares,
+        });
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_primitives::{address, keccak256};
+    use alloy_sol_types::{SolError, SolEvent};
+    use stylus_sdk::testing::*;
+
+    const ALICE: Address = address!("0x000000000000000000000000000000000000a11c");
+    const BOB: Address = address!("0x0000000000000000000000000000000000000b0b");
+
+    #[test]
+    fn test_role_ids() {
+        assert_eq!(keccak256("ORACLE_ROLE"), RebasingToken::oracle_role());
+        assert_eq!(keccak256("MINTER_ROLE"), RebasingToken::minter_role());
+    }
+
+    #[test]
+    fn test_rebase() {
+        let vm = TestVM::default();
+        vm.set_sender(ALICE);
+        let mut token = RebasingToken::from(&vm);
+        assert!(token.init("Staked Ether".into(), "stETH".into(), U256::from(1000)).is_ok());
+        assert!(token.init("Staked Ether".into(), "stETH".into(), U256::from(1000)).is_err());
+        assert!(token.transfer(BOB, U256::from(400)).is_ok());
+
+        // Balances grow with the pool, while shares stay put
+        assert!(token.rebase(U256::from(2000)).is_ok());
+        assert_eq!(U256::from(2000), token.total_supply());
+        assert_eq!(U256::from(1200), token.balance_of(ALICE));
+        assert_eq!(U256::from(800), token.balance_of(BOB));
+        assert_eq!(U256::from(600), token.shares_of(ALICE));
+        assert_eq!(U256::from(400), token.shares_of(BOB));
+
+        // Only oracles can rebase, and never to zero
+        vm.set_sender(BOB);
+        assert!(token.rebase(U256::from(3000)).is_err());
+        vm.set_sender(ALICE);
+        assert!(token.rebase(U256::ZERO).is_err());
+
+        // New tokens are minted at the current pool total
+        assert!(token.mint(BOB, U256::from(200)).is_ok());
+        assert_eq!(U256::from(500), token.shares_of(BOB));
+        assert_eq!(U256::from(1000), token.balance_of(BOB));
+        assert_eq!(U256::from(2200), token.total_supply());
+        vm.set_sender(BOB);
+        assert!(token.mint(BOB, U256::from(1)).is_err());
+    }
+
+    #[test]
+    fn test_transfer_shares() {
+        let vm = TestVM::default();
+        vm.set_sender(ALICE);
+        let mut token = RebasingToken::from(&vm);
+        assert!(token.init("Staked Ether".into(), "stETH".into(), U256::from(1000)).is_ok());
+        assert!(token.rebase(U256::from(3000)).is_ok());
+
+        // Transfer events are in token units, TransferShares in shares
+        assert_eq!(Some(U256::from(300)), token.transfer_shares(BOB, U256::from(100)).ok());
+        let logs = vm.get_emitted_logs();
+        let last_data = |signature| {
+            logs.iter()
+                .rev()
+                .find(|(topics, _)| topics[0] == signature)
+                .map(|(_, data)| U256::from_be_slice(data))
+        };
+        assert_eq!(Some(U256::from(300)), last_data(Transfer::SIGNATURE_HASH));
+        assert_eq!(Some(U256::from(100)), last_data(TransferShares::SIGNATURE_HASH));
+        assert_eq!(U256::from(300), token.balance_of(BOB));
+
+        // Token amounts are rounded down to whole shares
+        assert!(token.transfer(BOB, U256::from(302)).is_ok());
+        assert_eq!(U256::from(600), token.balance_of(BOB));
+        assert_eq!(U256::from(3000), token.balance_of(ALICE) + token.balance_of(BOB));
+
+        assert!(matches!(
+            token.transfer_shares(BOB, U256::from(801)),
+            Err(RebasingError::InsufficientShares(_))
+        ));
+        assert!(matches!(
+            token.transfer(Address::ZERO, U256::from(3)),
+            Err(RebasingError::InvalidReceiver(_))
+        ));
+        assert!(matches!(
+            token.transfer(BOB, U256::from(2)),
+            Err(RebasingError::ZeroShares(_))
+        ));
+
+        // Allowances are in token units
+        assert!(token.approve(BOB, U256::from(300)));
+        vm.set_sender(BOB);
+        assert!(token.transfer_from(ALICE, BOB, U256::from(300)).is_ok());
+        assert_eq!(U256::ZERO, token.allowance(ALICE, BOB));
+        assert_eq!(U256::from(900), token.balance_of(BOB));
+        assert!(matches!(
+            token.transfer_from(ALICE, BOB, U256::from(1)),
+            Err(RebasingError::InsufficientAllowance(_))
+        ));
+    }
+
+    #[test]
+    fn test_empty_pool() {
+        let vm = TestVM::default();
+        vm.set_sender(ALICE);
+        let mut token = RebasingToken::from(&vm);
+        assert!(token.init("Staked Ether".into(), "stETH".into(), U256::ZERO).is_ok());
+        assert_eq!(U256::ZERO, token.total_shares());
+
+        // A pool without shares can't be given a value, which new shares would not be worth
+        assert_eq!(
+            Err(InvalidRebase { new_total: U256::from(1000) }.abi_encode()),
+            token.rebase(U256::from(1000))
+        );
+        assert!(token.rebase(U256::ZERO).is_ok());
+
+        // So the first mint is priced 1:1
+        assert!(token.mint(BOB, U256::from(500)).is_ok());
+        assert_eq!(U256::from(500), token.shares_of(BOB));
+        assert_eq!(U256::from(500), token.balance_of(BOB));
+        assert_eq!(U256::from(500), token.total_supply());
+
+        // And amounts worth less than one share are refused
+        assert!(token.rebase(U256::from(1500)).is_ok());
+        assert_eq!(
+            Err(ZeroShares { value: U256::from(2) }.abi_encode()),
+            token.mint(BOB, U256::from(2))
+        );
+        assert_eq!(U256::from(500), token.total_shares());
+        assert_eq!(U256::from(1500), token.total_supply());
+    }
+}
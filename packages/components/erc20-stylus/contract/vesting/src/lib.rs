@@ -0,0 +1,570 @@
+//! Implementation of a token vesting wallet
+//!
+//! [`VestingWallet`] holds native ether and any number of ERC-20 tokens on
+//! behalf of a beneficiary, and releases them linearly between `start` and
+//! `start + duration`. Nothing can be released before the cliff.
+//!
+//! Every token sent to the wallet follows the same schedule, so the amount
+//! vested at any time is computed from the current balance plus what has
+//! already been released. If the wallet is revocable, the grantor can revoke
+//! an asset at any time: the unvested part is refunded to the grantor and the
+//! vested part stays releasable by the beneficiary.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(feature = "export-abi", test)), no_main)]
+extern crate alloc;
+
+// Modules and imports
+use alloc::vec::Vec;
+use alloy_primitives::{aliases::U64, Address, U256};
+use alloy_sol_types::{sol, SolCall};
+use stylus_sdk::{prelude::*, stylus_core::calls::context::Call};
+
+// Interface of the vested tokens
+sol! {
+    interface IERC20 {
+        function balanceOf(address account) external view returns (uint256);
+        function transfer(address to, uint256 value) external returns (bool);
+    }
+}
+
+// Define the entrypoint as a Solidity storage object. The sol_storage! macro
+// will generate Rust-equivalent structs with all fields mapped to Solidity-equivalent
+// storage slots and types.
+sol_storage! {
+    #[entrypoint]
+    pub struct VestingWallet {
+        /// Account receiving the vested assets
+        address beneficiary;
+        /// Account that set up the vesting, and may revoke it
+        address grantor;
+        /// Timestamp at which vesting starts
+        uint64 start;
+        /// Seconds after `start` before anything vests
+        uint64 cliff_duration;
+        /// Seconds after `start` until everything has vested
+        uint64 duration;
+        /// Whether the grantor may revoke unvested assets
+        bool revocable;
+        /// Ether released to the beneficiary so far
+        uint256 ether_released;
+        /// Whether the ether vesting was revoked
+        bool ether_revoked;
+        /// Maps tokens to the amount released to the beneficiary so far
+        mapping(address => uint256) erc20_released;
+        /// Maps tokens to whether their vesting was revoked
+        mapping(address => bool) erc20_revoked;
+        /// Whether init() has been called
+        bool initialized;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event EtherReleased(uint256 amount);
+    event ERC20Released(address indexed token, uint256 amount);
+    event EtherRevoked(uint256 refund);
+    event ERC20Revoked(address indexed token, uint256 refund);
+
+    error AlreadyInitialized();
+    error InvalidBeneficiary(address beneficiary);
+    error InvalidCliffDuration(uint64 cliff_duration, uint64 duration);
+    error VestingUnauthorizedAccount(address account);
+    error VestingNotRevocable();
+    error VestingAlreadyRevoked();
+    error EthTransferFailed(address to, uint256 value);
+    error SafeERC20FailedOperation(address token);
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum VestingError {
+    AlreadyInitialized(AlreadyInitialized),
+    InvalidBeneficiary(InvalidBeneficiary),
+    InvalidCliffDuration(InvalidCliffDuration),
+    UnauthorizedAccount(VestingUnauthorizedAccount),
+    NotRevocable(VestingNotRevocable),
+    AlreadyRevoked(VestingAlreadyRevoked),
+    EthTransferFailed(EthTransferFailed),
+    SafeErc20FailedOperation(SafeERC20FailedOperation),
+}
+
+// These methods aren't exposed to other contracts
+impl VestingWallet {
+    /// Amount of `total_allocation` vested at `timestamp`
+    /// (everything left is vested once the asset has been revoked)
+    pub fn vesting_schedule(&self, total_allocation: U256, timestamp: u64, revoked: bool) -> U256 {
+        let start = self.start();
+        if revoked || timestamp >= self.end() {
+            total_allocation
+        } else if timestamp < self.cliff() {
+            U256::ZERO
+        } else {
+            // total * elapsed / duration, split so the product can't overflow
+            let elapsed = U256::from(timestamp - start);
+            let duration = U256::from(self.duration());
+            (total_allocation / duration) * elapsed
+                + (total_allocation % duration) * elapsed / duration
+        }
+    }
+
+    /// Requires that msg::sender() is the grantor and that the wallet is revocable
+    fn require_revocable_by_sender(&self) -> Result<(), VestingError> {
+        let account = self.vm().msg_sender();
+        if account != self.grantor.get() {
+            return Err(VestingError::UnauthorizedAccount(VestingUnauthorizedAccount {
+                account,
+            }));
+        }
+        if !self.revocable.get() {
+            return Err(VestingError::NotRevocable(VestingNotRevocable {}));
+        }
+        Ok(())
+    }
+
+    /// Sends `value` wei to `to`
+    fn send_ether(&mut self, to: Address, value: U256) -> Result<(), VestingError> {
+        self.vm()
+            .transfer_eth(to, value)
+            .map_err(|_| VestingError::EthTransferFailed(EthTransferFailed { to, value }))
+    }
+
+    /// Sends `value` of `token` to `to`, requiring the token to either return `true` or,
+    /// like SafeERC20 allows for tokens such as USDT, return nothing at all
+    /// (the token's revert data is bubbled up)
+    fn send_erc20(&mut self, token: Address, to: Address, value: U256) -> Result<(), Vec<u8>> {
+        let calldata = IERC20::transferCall { to, value }.abi_encode();
+        let returned = self.vm().call(&Call::new(), token, &calldata)?;
+        let transferred = if returned.is_empty() {
+            // An empty return value only counts as a success from a contract
+            self.vm().code_size(token) > 0
+        } else {
+            IERC20::transferCall::abi_decode_returns(&returned, true)
+                .is_ok_and(|returned| returned._0)
+        };
+        if !transferred {
+            return Err(
+                VestingError::SafeErc20FailedOperation(SafeERC20FailedOperation { token }).into(),
+            );
+        }
+        Ok(())
+    }
+
+    /// Balance of `token` held by the wallet
+    fn erc20_balance(&self, token: Address) -> Result<U256, Vec<u8>> {
+        let calldata = IERC20::balanceOfCall {
+            account: self.vm().contract_address(),
+        }
+        .abi_encode();
+        let returned = self.vm().static_call(&Call::new(), token, &calldata)?;
+        match IERC20::balanceOfCall::abi_decode_returns(&returned, true) {
+            Ok(balance) => Ok(balance._0),
+            Err(_) => Err(
+                VestingError::SafeErc20FailedOperation(SafeERC20FailedOperation { token }).into(),
+            ),
+        }
+    }
+}
+
+// These methods are external to other contracts
+#[public]
+impl VestingWallet {
+    /// Sets up the vesting schedule, with msg::sender() as the grantor
+    /// (`start` is a timestamp, `cliff_duration` and `duration` are in seconds)
+    pub fn init(
+        &mut self,
+        beneficiary: Address,
+        start: u64,
+        cliff_duration: u64,
+        duration: u64,
+        revocable: bool,
+    ) -> Result<(), VestingError> {
+        if self.initialized.get() {
+            return Err(VestingError::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        if beneficiary.is_zero() {
+            return Err(VestingError::InvalidBeneficiary(InvalidBeneficiary {
+                beneficiary,
+            }));
+        }
+        if cliff_duration > duration {
+            return Err(VestingError::InvalidCliffDuration(InvalidCliffDuration {
+                cliff_duration,
+                duration,
+            }));
+        }
+
+        self.beneficiary.set(beneficiary);
+        self.grantor.set(self.vm().msg_sender());
+        self.start.set(U64::from(start));
+        self.cliff_duration.set(U64::from(cliff_duration));
+        self.duration.set(U64::from(duration));
+        self.revocable.set(revocable);
+        self.initialized.set(true);
+        Ok(())
+    }
+
+    /// Accepts ether to be vested
+    #[receive]
+    #[payable]
+    pub fn receive(&mut self) -> Result<(), Vec<u8>> {
+        Ok(())
+    }
+
+    /// Account receiving the vested assets
+    pub fn beneficiary(&self) -> Address {
+        self.beneficiary.get()
+    }
+
+    /// Account allowed to revoke the vesting
+    pub fn grantor(&self) -> Address {
+        self.grantor.get()
+    }
+
+    /// Timestamp at which vesting starts
+    pub fn start(&self) -> u64 {
+        self.start.get().to()
+    }
+
+    /// Timestamp before which nothing can be released
+    pub fn cliff(&self) -> u64 {
+        self.start().saturating_add(self.cliff_duration.get().to())
+    }
+
+    /// Vesting duration in seconds
+    pub fn duration(&self) -> u64 {
+        self.duration.get().to()
+    }
+
+    /// Timestamp at which everything has vested
+    pub fn end(&self) -> u64 {
+        self.start().saturating_add(self.duration())
+    }
+
+    /// Whether the grantor may revoke unvested assets
+    pub fn revocable(&self) -> bool {
+        self.revocable.get()
+    }
+
+    /// Ether released so far
+    #[selector(name = "released")]
+    pub fn ether_released(&self) -> U256 {
+        self.ether_released.get()
+    }
+
+    /// Amount of `token` released so far
+    #[selector(name = "released")]
+    pub fn erc20_released(&self, token: Address) -> U256 {
+        self.erc20_released.get(token)
+    }
+
+    /// Whether the ether vesting was revoked
+    #[selector(name = "revoked")]
+    pub fn ether_revoked(&self) -> bool {
+        self.ether_revoked.get()
+    }
+
+    /// Whether the vesting of `token` was revoked
+    #[selector(name = "revoked")]
+    pub fn erc20_revoked(&self, token: Address) -> bool {
+        self.erc20_revoked.get(token)
+    }
+
+    /// Ether vested at `timestamp`
+    #[selector(name = "vestedAmount")]
+    pub fn ether_vested_amount(&self, timestamp: u64) -> U256 {
+        let balance = self.vm().balance(self.vm().contract_address());
+        self.vesting_schedule(
+            balance + self.ether_released(),
+            timestamp,
+            self.ether_revoked(),
+        )
+    }
+
+    /// Amount of `token` vested at `timestamp`
+    #[selector(name = "vestedAmount")]
+    pub fn erc20_vested_amount(&self, token: Address, timestamp: u64) -> Result<U256, Vec<u8>> {
+        let balance = self.erc20_balance(token)?;
+        Ok(self.vesting_schedule(
+            balance + self.erc20_released(token),
+            timestamp,
+            self.erc20_revoked(token),
+        ))
+    }
+
+    /// Ether that can be released now
+    #[selector(name = "releasable")]
+    pub fn ether_releasable(&self) -> U256 {
+        self.ether_vested_amount(self.vm().block_timestamp()) - self.ether_released()
+    }
+
+    /// Amount of `token` that can be released now
+    #[selector(name = "releasable")]
+    pub fn erc20_releasable(&self, token: Address) -> Result<U256, Vec<u8>> {
+        let vested = self.erc20_vested_amount(token, self.vm().block_timestamp())?;
+        Ok(vested - self.erc20_released(token))
+    }
+
+    /// Sends the releasable ether to the beneficiary
+    #[selector(name = "release")]
+    pub fn release_ether(&mut self) -> Result<(), VestingError> {
+        let amount = self.ether_releasable();
+        self.ether_released.set(self.ether_released() + amount);
+        log(self.vm(), EtherReleased { amount });
+        self.send_ether(self.beneficiary(), amount)
+    }
+
+    /// Sends the releasable amount of `token` to the beneficiary
+    #[selector(name = "release")]
+    pub fn release_erc20(&mut self, token: Address) -> Result<(), Vec<u8>> {
+        let amount = self.erc20_releasable(token)?;
+        let released = self.erc20_released(token) + amount;
+        self.erc20_released.insert(token, released);
+        log(self.vm(), ERC20Released { token, amount });
+        self.send_erc20(token, self.beneficiary(), amount)
+    }
+
+    /// Refunds the unvested ether to the grantor (only callable by the grantor of a revocable wallet)
+    #[selector(name = "revoke")]
+    pub fn revoke_ether(&mut self) -> Result<(), VestingError> {
+        self.require_revocable_by_sender()?;
+        if self.ether_revoked() {
+            return Err(VestingError::AlreadyRevoked(VestingAlreadyRevoked {}));
+        }
+
+        let balance = self.vm().balance(self.vm().contract_address());
+        let refund = balance - self.ether_releasable();
+        self.ether_revoked.set(true);
+        log(self.vm(), EtherRevoked { refund });
+        self.send_ether(self.grantor(), refund)
+    }
+
+    /// Refunds the unvested amount of `token` to the grantor
+    /// (only callable by the grantor of a revocable wallet)
+    #[selector(name = "revoke")]
+    pub fn revoke_erc20(&mut self, token: Address) -> Result<(), Vec<u8>> {
+        self.require_revocable_by_sender()?;
+        if self.erc20_revoked(token) {
+            return Err(VestingError::AlreadyRevoked(VestingAlreadyRevoked {}).into());
+        }
+
+        let balance = self.erc20_balance(token)?;
+        let refund = balance - self.erc20_releasable(token)?;
+        self.erc20_revoked.insert(token, true);
+        log(self.vm(), ERC20Revoked { token, refund });
+        self.send_erc20(token, self.grantor(), refund)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_primitives::address;
+    use alloy_sol_types::{SolError, SolEvent, SolValue};
+    use stylus_sdk::testing::*;
+
+    const ALICE: Address = address!("0x000000000000000000000000000000000000a11c");
+    const BOB: Address = address!("0x0000000000000000000000000000000000000b0b");
+    const TOKEN: Address = address!("0x00000000000000000000000000000000000a55e7");
+
+    /// Wallet vesting 1000 wei to BOB from t=100 to t=300, with a cliff at t=150
+    fn setup(vm: &TestVM, revocable: bool) -> VestingWallet {
+        vm.set_sender(ALICE);
+        let mut wallet = VestingWallet::from(vm);
+        assert!(wallet.init(BOB, 100, 50, 200, revocable).is_ok());
+        vm.set_balance(vm.contract_address(), U256::from(1000));
+        wallet
+    }
+
+    /// Makes TOKEN report that the wallet holds `balance`
+    fn mock_balance(vm: &TestVM, balance: u64) {
+        let account = vm.contract_address();
+        let calldata = IERC20::balanceOfCall { account }.abi_encode();
+        vm.mock_static_call(TOKEN, calldata, Ok(U256::from(balance).abi_encode()));
+    }
+
+    /// Makes TOKEN answer a transfer of `value` from the wallet to `to` with `returned`
+    fn mock_transfer(vm: &TestVM, to: Address, value: u64, returned: Vec<u8>) {
+        let value = U256::from(value);
+        let calldata = IERC20::transferCall { to, value }.abi_encode();
+        vm.mock_call(TOKEN, calldata, Ok(returned));
+    }
+
+    /// Whether `event` was emitted
+    fn emitted<E: SolEvent>(vm: &TestVM, event: E) -> bool {
+        let log = event.encode_log_data();
+        vm.get_emitted_logs().contains(&(log.topics().to_vec(), log.data.to_vec()))
+    }
+
+    #[test]
+    fn test_init() {
+        let vm = TestVM::default();
+        vm.set_sender(ALICE);
+        let mut wallet = VestingWallet::from(&vm);
+        assert!(matches!(
+            wallet.init(Address::ZERO, 100, 50, 200, false),
+            Err(VestingError::InvalidBeneficiary(_))
+        ));
+        assert!(matches!(
+            wallet.init(BOB, 100, 250, 200, false),
+            Err(VestingError::InvalidCliffDuration(_))
+        ));
+
+        assert!(wallet.init(BOB, 100, 50, 200, false).is_ok());
+        assert_eq!(BOB, wallet.beneficiary());
+        assert_eq!(ALICE, wallet.grantor());
+        assert_eq!(150, wallet.cliff());
+        assert_eq!(300, wallet.end());
+        assert!(matches!(
+            wallet.init(BOB, 100, 50, 200, false),
+            Err(VestingError::AlreadyInitialized(_))
+        ));
+    }
+
+    #[test]
+    fn test_vesting_schedule() {
+        let vm = TestVM::default();
+        let wallet = setup(&vm, false);
+        let total = U256::from(1000);
+
+        assert_eq!(U256::ZERO, wallet.vesting_schedule(total, 0, false));
+        assert_eq!(U256::ZERO, wallet.vesting_schedule(total, 149, false));
+        assert_eq!(U256::from(250), wallet.vesting_schedule(total, 150, false));
+        assert_eq!(U256::from(500), wallet.vesting_schedule(total, 200, false));
+        assert_eq!(total, wallet.vesting_schedule(total, 300, false));
+        assert_eq!(total, wallet.vesting_schedule(total, 149, true));
+
+        // No overflow on huge allocations
+        assert_eq!(
+            U256::MAX / U256::from(2),
+            wallet.vesting_schedule(U256::MAX, 200, false)
+        );
+    }
+
+    #[test]
+    fn test_release_ether() {
+        let vm = TestVM::default();
+        let mut wallet = setup(&vm, false);
+
+        vm.set_block_timestamp(120);
+        assert_eq!(U256::ZERO, wallet.ether_releasable());
+
+        vm.set_block_timestamp(200);
+        assert_eq!(U256::from(500), wallet.ether_releasable());
+        assert!(wallet.release_ether().is_ok());
+        assert_eq!(U256::from(500), vm.balance(BOB));
+        assert_eq!(U256::from(500), wallet.ether_released());
+        assert_eq!(U256::ZERO, wallet.ether_releasable());
+
+        // Earlier releases are accounted for
+        vm.set_block_timestamp(250);
+        assert_eq!(U256::from(250), wallet.ether_releasable());
+
+        vm.set_block_timestamp(1000);
+        assert!(wallet.release_ether().is_ok());
+        assert_eq!(U256::from(1000), vm.balance(BOB));
+        assert_eq!(U256::ZERO, vm.balance(vm.contract_address()));
+
+        // Only the grantor of a revocable wallet can revoke
+        assert!(matches!(
+            wallet.revoke_ether(),
+            Err(VestingError::NotRevocable(_))
+        ));
+    }
+
+    #[test]
+    fn test_revoke_ether() {
+        let vm = TestVM::default();
+        let mut wallet = setup(&vm, true);
+        vm.set_block_timestamp(200);
+
+        vm.set_sender(BOB);
+        assert!(matches!(
+            wallet.revoke_ether(),
+            Err(VestingError::UnauthorizedAccount(_))
+        ));
+
+        // The unvested half goes back to the grantor
+        vm.set_sender(ALICE);
+        assert!(wallet.revoke_ether().is_ok());
+        assert!(wallet.ether_revoked());
+        assert_eq!(U256::from(500), vm.balance(ALICE));
+        assert!(matches!(
+            wallet.revoke_ether(),
+            Err(VestingError::AlreadyRevoked(_))
+        ));
+
+        // And the vested half stays releasable
+        assert_eq!(U256::from(500), wallet.ether_releasable());
+        assert!(wallet.release_ether().is_ok());
+        assert_eq!(U256::from(500), vm.balance(BOB));
+    }
+
+    #[test]
+    fn test_release_erc20() {
+        let vm = TestVM::default();
+        let mut wallet = setup(&vm, false);
+        vm.set_code(TOKEN, vec![0xfe]);
+        mock_balance(&vm, 1000);
+
+        vm.set_block_timestamp(200);
+        assert_eq!(Ok(U256::from(500)), wallet.erc20_releasable(TOKEN));
+        mock_transfer(&vm, BOB, 500, true.abi_encode());
+        assert!(wallet.release_erc20(TOKEN).is_ok());
+        assert_eq!(U256::from(500), wallet.erc20_released(TOKEN));
+        assert!(emitted(&vm, ERC20Released { token: TOKEN, amount: U256::from(500) }));
+
+        // Earlier releases are accounted for, and tokens returning nothing (like USDT) are accepted
+        mock_balance(&vm, 500);
+        vm.set_block_timestamp(250);
+        assert_eq!(Ok(U256::from(250)), wallet.erc20_releasable(TOKEN));
+        mock_transfer(&vm, BOB, 250, Vec::new());
+        assert!(wallet.release_erc20(TOKEN).is_ok());
+        assert_eq!(U256::from(750), wallet.erc20_released(TOKEN));
+        assert!(emitted(&vm, ERC20Released { token: TOKEN, amount: U256::from(250) }));
+
+        // A token reporting a failed transfer is refused
+        mock_balance(&vm, 250);
+        vm.set_block_timestamp(1000);
+        mock_transfer(&vm, BOB, 250, false.abi_encode());
+        assert_eq!(
+            Err(SafeERC20FailedOperation { token: TOKEN }.abi_encode()),
+            wallet.release_erc20(TOKEN)
+        );
+    }
+
+    #[test]
+    fn test_revoke_erc20() {
+        let vm = TestVM::default();
+        let mut wallet = setup(&vm, true);
+        vm.set_code(TOKEN, vec![0xfe]);
+        mock_balance(&vm, 1000);
+        vm.set_block_timestamp(200);
+
+        vm.set_sender(BOB);
+        assert_eq!(
+            Err(VestingUnauthorizedAccount { account: BOB }.abi_encode()),
+            wallet.revoke_erc20(TOKEN)
+        );
+
+        // The unvested half goes back to the grantor
+        vm.set_sender(ALICE);
+        mock_transfer(&vm, ALICE, 500, true.abi_encode());
+        assert!(wallet.revoke_erc20(TOKEN).is_ok());
+        assert!(wallet.erc20_revoked(TOKEN));
+        assert!(emitted(&vm, ERC20Revoked { token: TOKEN, refund: U256::from(500) }));
+        assert_eq!(
+            Err(VestingAlreadyRevoked {}.abi_encode()),
+            wallet.revoke_erc20(TOKEN)
+        );
+
+        // And the vested half stays releasable
+        mock_balance(&vm, 500);
+        assert_eq!(Ok(U256::from(500)), wallet.erc20_releasable(TOKEN));
+        mock_transfer(&vm, BOB, 500, true.abi_encode());
+        assert!(wallet.release_erc20(TOKEN).is_ok());
+        assert!(emitted(&vm, ERC20Released { token: TOKEN, amount: U256::from(500) }));
+    }
+}
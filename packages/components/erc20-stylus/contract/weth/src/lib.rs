@@ -0,0 +1,217 @@
+//! Implementation of Wrapped Ether (WETH)
+//!
+//! [`WrappedEther`] mints one token for every wei deposited and burns it again
+//! when the ether is withdrawn, so the token is always fully backed by the
+//! contract balance. The ERC-20 side is the shared [`Erc20`] module.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(feature = "export-abi", test)), no_main)]
+extern crate alloc;
+
+// Modules and imports
+use alloc::vec::Vec;
+use alloy_primitives::U256;
+use alloy_sol_types::sol;
+use erc20::{Erc20, Erc20Hooks, Erc20Params, NoStorage};
+use stylus_sdk::prelude::*;
+
+/// Immutable definitions
+pub struct WrappedEtherParams;
+impl Erc20Params for WrappedEtherParams {
+    const DECIMALS: u8 = 18;
+
+    // Flash minting would let a borrower hold unbacked WETH for the length of the loan
+    const FLASH_LOANS: bool = false;
+}
+impl Erc20Hooks for WrappedEtherParams {
+    type Storage = NoStorage;
+}
+
+// Define the entrypoint as a Solidity storage object. The sol_storage! macro
+// will generate Rust-equivalent structs with all fields mapped to Solidity-equivalent
+// storage slots and types.
+sol_storage! {
+    #[entrypoint]
+    pub struct WrappedEther {
+        // Allows erc20 to access WrappedEther's storage and make calls
+        #[borrow]
+        Erc20<WrappedEtherParams> erc20;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event Deposit(address indexed account, uint256 value);
+    event Withdrawal(address indexed account, uint256 value);
+
+    error EthTransferFailed(address to, uint256 value);
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum WrappedEtherError {
+    EthTransferFailed(EthTransferFailed),
+}
+
+// These methods aren't exposed to other contracts
+impl WrappedEther {
+    /// Mints msg::value() tokens to msg::sender()
+    fn _deposit(&mut self) -> Result<(), Vec<u8>> {
+        let account = self.vm().msg_sender();
+        let value = self.vm().msg_value();
+        self.erc20.mint(account, value)?;
+        log(self.vm(), Deposit { account, value });
+        Ok(())
+    }
+}
+
+#[public]
+#[inherit(Erc20<WrappedEtherParams>)]
+impl WrappedEther {
+    /// Writes the token metadata to storage, where the EIP-712 domain of `permit` and
+    /// `delegateBySig` reads its name from (can only be called once)
+    pub fn init(&mut self) -> Result<(), Vec<u8>> {
+        self.erc20.init("Wrapped Ether".into(), "WETH".into(), U256::ZERO)?;
+        Ok(())
+    }
+
+    /// Wraps the ether sent with the call into the same amount of tokens
+    #[payable]
+    pub fn deposit(&mut self) -> Result<(), Vec<u8>> {
+        self._deposit()
+    }
+
+    /// Burns `value` tokens of msg::sender() and sends back the same amount of ether
+    pub fn withdraw(&mut self, value: U256) -> Result<(), Vec<u8>> {
+        let account = self.vm().msg_sender();
+
+        // Tokens are burned before the ether leaves, so a reentrant call can't withdraw twice
+        self.erc20.burn(account, value)?;
+        self.vm().transfer_eth(account, value).map_err(|_| {
+            WrappedEtherError::EthTransferFailed(EthTransferFailed { to: account, value })
+        })?;
+
+        log(self.vm(), Withdrawal { account, value });
+        Ok(())
+    }
+
+    /// Plain ether transfers are deposited as well
+    #[receive]
+    #[payable]
+    pub fn receive(&mut self) -> Result<(), Vec<u8>> {
+        self._deposit()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_primitives::{address, Address};
+    use alloy_sol_types::{SolError, SolEvent};
+    use stylus_sdk::testing::*;
+
+    const ALICE: Address = address!("0x000000000000000000000000000000000000a11c");
+    const BOB: Address = address!("0x0000000000000000000000000000000000000b0b");
+
+    /// Whether `event` was emitted
+    fn emitted<E: SolEvent>(vm: &TestVM, event: E) -> bool {
+        let log = event.encode_log_data();
+        vm.get_emitted_logs().contains(&(log.topics().to_vec(), log.data.to_vec()))
+    }
+
+    #[test]
+    fn test_init() {
+        let vm = TestVM::default();
+        vm.set_sender(ALICE);
+        let mut contract = WrappedEther::from(&vm);
+        assert!(contract.init().is_ok());
+        assert!(contract.init().is_err());
+
+        assert_eq!("Wrapped Ether", contract.erc20.name());
+        assert_eq!("WETH", contract.erc20.symbol());
+        assert_eq!(18, contract.erc20.decimals());
+        assert_eq!(U256::ZERO, contract.erc20.total_supply());
+        assert_eq!(U256::ZERO, contract.erc20.max_flash_loan(vm.contract_address()));
+    }
+
+    #[test]
+    fn test_deposit() {
+        let vm = TestVM::default();
+        vm.set_sender(ALICE);
+        let mut contract = WrappedEther::from(&vm);
+        assert!(contract.init().is_ok());
+
+        // Both deposit() and plain transfers mint one token per wei sent
+        vm.set_value(U256::from(100));
+        assert!(contract.deposit().is_ok());
+        assert!(emitted(&vm, Deposit {
+            account: ALICE,
+            value: U256::from(100),
+        }));
+        vm.set_sender(BOB);
+        vm.set_value(U256::from(40));
+        assert!(contract.receive().is_ok());
+        assert!(emitted(&vm, Deposit {
+            account: BOB,
+            value: U256::from(40),
+        }));
+
+        assert_eq!(U256::from(100), contract.erc20.balance_of(ALICE));
+        assert_eq!(U256::from(40), contract.erc20.balance_of(BOB));
+        assert_eq!(U256::from(140), contract.erc20.total_supply());
+    }
+
+    #[test]
+    fn test_withdraw() {
+        let vm = TestVM::default();
+        vm.set_sender(ALICE);
+        let mut contract = WrappedEther::from(&vm);
+        assert!(contract.init().is_ok());
+        vm.set_value(U256::from(200));
+        assert!(contract.deposit().is_ok());
+
+        // The test VM doesn't credit msg.value to the contract, so fund it explicitly
+        vm.set_value(U256::ZERO);
+        vm.set_balance(vm.contract_address(), U256::from(200));
+        assert!(contract.withdraw(U256::from(150)).is_ok());
+        assert_eq!(U256::from(50), contract.erc20.balance_of(ALICE));
+        assert_eq!(U256::from(50), contract.erc20.total_supply());
+        assert_eq!(U256::from(150), vm.balance(ALICE));
+        assert_eq!(U256::from(50), vm.balance(vm.contract_address()));
+        assert!(emitted(&vm, Withdrawal {
+            account: ALICE,
+            value: U256::from(150),
+        }));
+
+        // Withdrawing more than the wrapped balance fails without sending anything
+        assert!(contract.withdraw(U256::from(51)).is_err());
+        assert_eq!(U256::from(150), vm.balance(ALICE));
+        assert_eq!(U256::from(50), vm.balance(vm.contract_address()));
+    }
+
+    #[test]
+    fn test_withdraw_failed_send() {
+        let vm = TestVM::default();
+        vm.set_sender(ALICE);
+        let mut contract = WrappedEther::from(&vm);
+        assert!(contract.init().is_ok());
+        vm.set_value(U256::from(100));
+        assert!(contract.deposit().is_ok());
+
+        // The contract holds no ether, so the send fails and the withdrawal reverts
+        vm.set_value(U256::ZERO);
+        let failed = EthTransferFailed {
+            to: ALICE,
+            value: U256::from(60),
+        };
+        assert_eq!(Err(failed.abi_encode()), contract.withdraw(U256::from(60)));
+        assert_eq!(U256::ZERO, vm.balance(ALICE));
+
+        // The test VM doesn't roll the revert back, which shows the tokens were
+        // already burned when the ether was sent
+        assert_eq!(U256::from(40), contract.erc20.balance_of(ALICE));
+        assert_eq!(U256::from(40), contract.erc20.total_supply());
+    }
+}
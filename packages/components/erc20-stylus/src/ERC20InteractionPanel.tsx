@@ -91,6 +91,7 @@ const ERC20_ABI = [
     "function mint(uint256 value)",
     "function mintTo(address to, uint256 value)",
     "function burn(uint256 value)",
+    "function burnFrom(address account, uint256 value)",
 ];
 
 // Network-specific default contract addresses (only for networks where contracts are deployed)
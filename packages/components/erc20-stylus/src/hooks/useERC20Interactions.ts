@@ -3,7 +3,7 @@
  */
 
 import { useState, useCallback, useEffect } from 'react';
-import type { Address, Hash, PublicClient, WalletClient } from 'viem';
+import type { Address, Hash, Hex, PublicClient, WalletClient } from 'viem';
 import { parseUnits, formatUnits } from 'viem';
 import { ERC20_ABI, TOKEN_DECIMALS, getRpcEndpoint } from '../constants';
 import type { 
@@ -26,6 +26,8 @@ export function useERC20Interactions(options: UseERC20InteractionsOptions): UseE
   } = options;
 
   const [tokenInfo, setTokenInfo] = useState<AsyncState<TokenInfo>>({ status: 'idle' });
+  // Amounts use the decimals stored by the contract, once they have been fetched
+  const decimals = tokenInfo.status === 'success' ? tokenInfo.data.decimals : TOKEN_DECIMALS;
   const [balance, setBalance] = useState<AsyncState<BalanceInfo>>({ status: 'idle' });
   const [txState, setTxState] = useState<TransactionState>({ status: 'idle' });
   const [error, setError] = useState<Error | null>(null);
@@ -106,13 +108,13 @@ export function useERC20Interactions(options: UseERC20InteractionsOptions): UseE
         status: 'success',
         data: {
           balance: balanceValue,
-          formatted: formatUnits(balanceValue, TOKEN_DECIMALS),
+          formatted: formatUnits(balanceValue, decimals),
         },
       });
     } catch (err) {
       setBalance({ status: 'error', error: err instanceof Error ? err : new Error(String(err)) });
     }
-  }, [publicClient, contractAddress, userAddress]);
+  }, [decimals, publicClient, contractAddress, userAddress]);
 
   // Fetch on mount
   useEffect(() => {
@@ -134,9 +136,9 @@ export function useERC20Interactions(options: UseERC20InteractionsOptions): UseE
     
     return {
       allowance,
-      formatted: formatUnits(allowance, TOKEN_DECIMALS),
+      formatted: formatUnits(allowance, decimals),
     };
-  }, [publicClient, contractAddress, userAddress]);
+  }, [decimals, publicClient, contractAddress, userAddress]);
 
   // Helper to execute a write transaction
   const executeTransaction = useCallback(async (
@@ -177,43 +179,52 @@ export function useERC20Interactions(options: UseERC20InteractionsOptions): UseE
 
   // Transfer
   const transfer = useCallback(async (to: Address, amount: string): Promise<Hash> => {
-    const amountWei = parseUnits(amount, TOKEN_DECIMALS);
+    const amountWei = parseUnits(amount, decimals);
     const hash = await executeTransaction('transfer', [to, amountWei]);
     refetchBalance();
     return hash;
-  }, [executeTransaction, refetchBalance]);
+  }, [decimals, executeTransaction, refetchBalance]);
 
   // Approve
   const approve = useCallback(async (spender: Address, amount: string): Promise<Hash> => {
-    const amountWei = parseUnits(amount, TOKEN_DECIMALS);
+    const amountWei = parseUnits(amount, decimals);
     return executeTransaction('approve', [spender, amountWei]);
-  }, [executeTransaction]);
+  }, [decimals, executeTransaction]);
 
   // Transfer from
   const transferFrom = useCallback(async (from: Address, to: Address, amount: string): Promise<Hash> => {
-    const amountWei = parseUnits(amount, TOKEN_DECIMALS);
+    const amountWei = parseUnits(amount, decimals);
     const hash = await executeTransaction('transferFrom', [from, to, amountWei]);
     refetchBalance();
     return hash;
-  }, [executeTransaction, refetchBalance]);
+  }, [decimals, executeTransaction, refetchBalance]);
 
   // Mint
   const mint = useCallback(async (to: Address, amount: string): Promise<Hash> => {
-    const amountWei = parseUnits(amount, TOKEN_DECIMALS);
+    const amountWei = parseUnits(amount, decimals);
     const hash = await executeTransaction('mint', [to, amountWei]);
     refetchTokenInfo();
     refetchBalance();
     return hash;
-  }, [executeTransaction, refetchTokenInfo, refetchBalance]);
+  }, [decimals, executeTransaction, refetchTokenInfo, refetchBalance]);
 
   // Burn
   const burn = useCallback(async (amount: string): Promise<Hash> => {
-    const amountWei = parseUnits(amount, TOKEN_DECIMALS);
+    const amountWei = parseUnits(amount, decimals);
     const hash = await executeTransaction('burn', [amountWei]);
     refetchTokenInfo();
     refetchBalance();
     return hash;
-  }, [executeTransaction, refetchTokenInfo, refetchBalance]);
+  }, [decimals, executeTransaction, refetchTokenInfo, refetchBalance]);
+
+  // Burn from another account
+  const burnFrom = useCallback(async (account: Address, amount: string): Promise<Hash> => {
+    const amountWei = parseUnits(amount, decimals);
+    const hash = await executeTransaction('burnFrom', [account, amountWei]);
+    refetchTokenInfo();
+    refetchBalance();
+    return hash;
+  }, [decimals, executeTransaction, refetchTokenInfo, refetchBalance]);
 
   // Pause
   const pause = useCallback(async (): Promise<Hash> => {
@@ -229,6 +240,16 @@ export function useERC20Interactions(options: UseERC20InteractionsOptions): UseE
     return hash;
   }, [executeTransaction, refetchTokenInfo]);
 
+  // Grant role
+  const grantRole = useCallback(async (role: Hex, account: Address): Promise<Hash> => {
+    return executeTransaction('grantRole', [role, account]);
+  }, [executeTransaction]);
+
+  // Revoke role
+  const revokeRole = useCallback(async (role: Hex, account: Address): Promise<Hash> => {
+    return executeTransaction('revokeRole', [role, account]);
+  }, [executeTransaction]);
+
   // Transfer ownership
   const transferOwnership = useCallback(async (newOwner: Address): Promise<Hash> => {
     const hash = await executeTransaction('transferOwnership', [newOwner]);
@@ -247,8 +268,11 @@ export function useERC20Interactions(options: UseERC20InteractionsOptions): UseE
     transferFrom,
     mint,
     burn,
+    burnFrom,
     pause,
     unpause,
+    grantRole,
+    revokeRole,
     transferOwnership,
     txState,
     isLoading: txState.status === 'pending' || txState.status === 'confirming',
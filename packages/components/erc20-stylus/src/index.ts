@@ -55,8 +55,11 @@ export {
   transferFrom,
   mint,
   burn,
+  burnFrom,
   pause,
   unpause,
+  grantRole,
+  revokeRole,
   transferOwnership,
 } from './interactions';
 
@@ -3,10 +3,17 @@
  */
 
 import { ethers } from 'ethers';
-import type { Address, Hash } from 'viem';
-import { ERC20_ABI, TOKEN_DECIMALS } from './constants';
+import type { Address, Hash, Hex } from 'viem';
+import { ERC20_ABI } from './constants';
 import type { TokenInfo, BalanceInfo, AllowanceInfo } from './types';
 
+/**
+ * Decimals stored by the contract, set on `init`
+ */
+async function getDecimals(contract: ethers.Contract): Promise<number> {
+  return Number(await contract.decimals());
+}
+
 /**
  * Get token information
  */
@@ -53,7 +60,7 @@ export async function getBalance(
   
   return {
     balance: BigInt(balance),
-    formatted: ethers.formatUnits(balance, TOKEN_DECIMALS),
+    formatted: ethers.formatUnits(balance, await getDecimals(contract)),
   };
 }
 
@@ -73,7 +80,7 @@ export async function getAllowance(
   
   return {
     allowance: BigInt(allowance),
-    formatted: ethers.formatUnits(allowance, TOKEN_DECIMALS),
+    formatted: ethers.formatUnits(allowance, await getDecimals(contract)),
   };
 }
 
@@ -91,7 +98,7 @@ export async function transfer(
   const wallet = new ethers.Wallet(privateKey, provider);
   const contract = new ethers.Contract(contractAddress, ERC20_ABI, wallet);
 
-  const amountWei = ethers.parseUnits(amount, TOKEN_DECIMALS);
+  const amountWei = ethers.parseUnits(amount, await getDecimals(contract));
   const tx = await contract.transfer(to, amountWei);
   const receipt = await tx.wait();
   
@@ -112,7 +119,7 @@ export async function approve(
   const wallet = new ethers.Wallet(privateKey, provider);
   const contract = new ethers.Contract(contractAddress, ERC20_ABI, wallet);
 
-  const amountWei = ethers.parseUnits(amount, TOKEN_DECIMALS);
+  const amountWei = ethers.parseUnits(amount, await getDecimals(contract));
   const tx = await contract.approve(spender, amountWei);
   const receipt = await tx.wait();
   
@@ -134,7 +141,7 @@ export async function transferFrom(
   const wallet = new ethers.Wallet(privateKey, provider);
   const contract = new ethers.Contract(contractAddress, ERC20_ABI, wallet);
 
-  const amountWei = ethers.parseUnits(amount, TOKEN_DECIMALS);
+  const amountWei = ethers.parseUnits(amount, await getDecimals(contract));
   const tx = await contract.transferFrom(from, to, amountWei);
   const receipt = await tx.wait();
   
@@ -142,7 +149,7 @@ export async function transferFrom(
 }
 
 /**
- * Mint new tokens (owner only)
+ * Mint new tokens (MINTER_ROLE only)
  */
 export async function mint(
   contractAddress: Address,
@@ -155,7 +162,7 @@ export async function mint(
   const wallet = new ethers.Wallet(privateKey, provider);
   const contract = new ethers.Contract(contractAddress, ERC20_ABI, wallet);
 
-  const amountWei = ethers.parseUnits(amount, TOKEN_DECIMALS);
+  const amountWei = ethers.parseUnits(amount, await getDecimals(contract));
   const tx = await contract.mint(to, amountWei);
   const receipt = await tx.wait();
   
@@ -175,7 +182,7 @@ export async function burn(
   const wallet = new ethers.Wallet(privateKey, provider);
   const contract = new ethers.Contract(contractAddress, ERC20_ABI, wallet);
 
-  const amountWei = ethers.parseUnits(amount, TOKEN_DECIMALS);
+  const amountWei = ethers.parseUnits(amount, await getDecimals(contract));
   const tx = await contract.burn(amountWei);
   const receipt = await tx.wait();
   
@@ -183,7 +190,28 @@ export async function burn(
 }
 
 /**
- * Pause token transfers (owner only)
+ * Burn tokens of another account using the caller's allowance
+ */
+export async function burnFrom(
+  contractAddress: Address,
+  account: Address,
+  amount: string,
+  privateKey: string,
+  rpcEndpoint: string
+): Promise<Hash> {
+  const provider = new ethers.JsonRpcProvider(rpcEndpoint);
+  const wallet = new ethers.Wallet(privateKey, provider);
+  const contract = new ethers.Contract(contractAddress, ERC20_ABI, wallet);
+
+  const amountWei = ethers.parseUnits(amount, await getDecimals(contract));
+  const tx = await contract.burnFrom(account, amountWei);
+  const receipt = await tx.wait();
+  
+  return receipt.hash as Hash;
+}
+
+/**
+ * Pause token transfers (PAUSER_ROLE only)
  */
 export async function pause(
   contractAddress: Address,
@@ -201,7 +229,7 @@ export async function pause(
 }
 
 /**
- * Unpause token transfers (owner only)
+ * Unpause token transfers (PAUSER_ROLE only)
  */
 export async function unpause(
   contractAddress: Address,
@@ -219,7 +247,47 @@ export async function unpause(
 }
 
 /**
- * Transfer ownership (owner only)
+ * Grant a role to an account (role admin only)
+ */
+export async function grantRole(
+  contractAddress: Address,
+  role: Hex,
+  account: Address,
+  privateKey: string,
+  rpcEndpoint: string
+): Promise<Hash> {
+  const provider = new ethers.JsonRpcProvider(rpcEndpoint);
+  const wallet = new ethers.Wallet(privateKey, provider);
+  const contract = new ethers.Contract(contractAddress, ERC20_ABI, wallet);
+
+  const tx = await contract.grantRole(role, account);
+  const receipt = await tx.wait();
+  
+  return receipt.hash as Hash;
+}
+
+/**
+ * Revoke a role from an account (role admin only)
+ */
+export async function revokeRole(
+  contractAddress: Address,
+  role: Hex,
+  account: Address,
+  privateKey: string,
+  rpcEndpoint: string
+): Promise<Hash> {
+  const provider = new ethers.JsonRpcProvider(rpcEndpoint);
+  const wallet = new ethers.Wallet(privateKey, provider);
+  const contract = new ethers.Contract(contractAddress, ERC20_ABI, wallet);
+
+  const tx = await contract.revokeRole(role, account);
+  const receipt = await tx.wait();
+  
+  return receipt.hash as Hash;
+}
+
+/**
+ * Transfer ownership, and the admin role with it (owner only)
  */
 export async function transferOwnership(
   contractAddress: Address,
@@ -2,7 +2,7 @@
  * ERC20 Stylus Types
  */
 
-import type { Address, Hash, PublicClient, WalletClient } from 'viem';
+import type { Address, Hash, Hex, PublicClient, WalletClient } from 'viem';
 import type { SupportedNetwork } from './constants';
 
 /**
@@ -144,8 +144,11 @@ export interface UseERC20InteractionsReturn {
   transferFrom: (from: Address, to: Address, amount: string) => Promise<Hash>;
   mint: (to: Address, amount: string) => Promise<Hash>;
   burn: (amount: string) => Promise<Hash>;
+  burnFrom: (account: Address, amount: string) => Promise<Hash>;
   pause: () => Promise<Hash>;
   unpause: () => Promise<Hash>;
+  grantRole: (role: Hex, account: Address) => Promise<Hash>;
+  revokeRole: (role: Hex, account: Address) => Promise<Hash>;
   transferOwnership: (newOwner: Address) => Promise<Hash>;
   
   // Transaction state
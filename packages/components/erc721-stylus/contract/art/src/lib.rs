@@ -0,0 +1,230 @@
+//! Implementation of an on-chain art generator for the Stylus ERC-721 collection
+//!
+//! [`NftArt`] answers the `tokenURI` calls of the NFT contract it was
+//! initialized with: `generateArt` returns a `data:application/json;base64,`
+//! URI whose `image` is an embedded SVG. The picture only depends on the
+//! keccak256 hash of the token id, so a token always looks the same,
+//! whoever owns it; the metadata around it names the current owner.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Only run this as a WASM if the export-abi feature is not set.
+#![cfg_attr(not(any(feature = "export-abi", test)), no_main)]
+extern crate alloc;
+
+// Modules and imports
+use alloc::{format, string::String, vec::Vec};
+use alloy_primitives::{keccak256, Address, B256, U256};
+use alloy_sol_types::sol;
+use stylus_sdk::prelude::*;
+
+/// Side of the (square) SVG canvas
+const CANVAS_SIZE: u32 = 350;
+
+/// Number of circles drawn on each token
+const CIRCLES: usize = 4;
+
+/// Alphabet of the standard base64 encoding
+const BASE64_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+// Define the entrypoint as a Solidity storage object. The sol_storage! macro
+// will generate Rust-equivalent structs with all fields mapped to Solidity-equivalent
+// storage slots and types.
+sol_storage! {
+    #[entrypoint]
+    pub struct NftArt {
+        /// NFT contract this generator draws for
+        address token_contract_address;
+    }
+}
+
+// Declare Solidity error types
+sol! {
+    /// Contract has already been initialized
+    error AlreadyInitialized();
+    /// Only the NFT contract itself may initialize its generator
+    error UnauthorizedInitializer(address sender, address token_contract_address);
+    /// The NFT contract can't be the zero address
+    error InvalidTokenContract();
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum NftArtError {
+    AlreadyInitialized(AlreadyInitialized),
+    UnauthorizedInitializer(UnauthorizedInitializer),
+    InvalidTokenContract(InvalidTokenContract),
+}
+
+/// Encodes `data` with the standard, padded base64 alphabet
+pub fn base64_encode(data: &[u8]) -> String {
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+    for chunk in data.chunks(3) {
+        let bytes = [chunk[0], *chunk.get(1).unwrap_or(&0), *chunk.get(2).unwrap_or(&0)];
+        let triple = (bytes[0] as u32) << 16 | (bytes[1] as u32) << 8 | bytes[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                let sextet = (triple >> (18 - 6 * i)) & 0x3f;
+                encoded.push(BASE64_ALPHABET[sextet as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// Seed every visual parameter of `token_id` is read from
+fn seed(token_id: U256) -> B256 {
+    keccak256(token_id.to_be_bytes::<32>())
+}
+
+/// Main hue of the token drawn from `seed`, in degrees
+fn hue(seed: &B256) -> u32 {
+    (u32::from(seed[0]) << 8 | u32::from(seed[1])) % 360
+}
+
+/// Draws the SVG of `token_id`: a two-tone gradient background with a few
+/// translucent circles, every parameter being read from the hash of the id
+pub fn render_svg(token_id: U256) -> String {
+    let seed = seed(token_id);
+    let byte = |i: usize| u32::from(seed[i]);
+    let hue = hue(&seed);
+    let accent = (hue + 120 + byte(2) % 120) % 360;
+
+    let circles: Vec<String> = (0..CIRCLES)
+        .map(|i| {
+            let offset = 3 + 4 * i;
+            format!(
+                "<circle cx='{}' cy='{}' r='{}' fill='hsl({},80%,60%)' fill-opacity='0.6'/>",
+                byte(offset) * CANVAS_SIZE / 256,
+                byte(offset + 1) * CANVAS_SIZE / 256,
+                20 + byte(offset + 2) % 80,
+                (accent + byte(offset + 3) % 60) % 360,
+            )
+        })
+        .collect();
+
+    format!(
+        "<svg xmlns='http://www.w3.org/2000/svg' viewBox='0 0 {size} {size}'>\
+<defs><linearGradient id='bg' x1='0' y1='0' x2='1' y2='1'>\
+<stop offset='0' stop-color='hsl({hue},70%,25%)'/>\
+<stop offset='1' stop-color='hsl({accent},70%,15%)'/>\
+</linearGradient></defs>\
+<rect width='{size}' height='{size}' fill='url(#bg)'/>{circles}\
+<text x='20' y='{text_y}' fill='white' font-family='monospace' font-size='20'>\
+#{token_id}</text>\
+</svg>",
+        size = CANVAS_SIZE,
+        text_y = CANVAS_SIZE - 20,
+        circles = circles.concat(),
+    )
+}
+
+/// Builds the metadata JSON of `token_id`, embedding its SVG as a `data:` URI
+/// and listing its current `owner` among the attributes
+pub fn metadata_json(token_id: U256, owner: Address) -> String {
+    let hue = hue(&seed(token_id));
+    let image = base64_encode(render_svg(token_id).as_bytes());
+    format!(
+        "{{\"name\":\"Robinhood NFT #{token_id}\",\
+\"description\":\"Generative art, drawn on-chain from the token id.\",\
+\"image\":\"data:image/svg+xml;base64,{image}\",\
+\"attributes\":[{{\"trait_type\":\"Hue\",\"value\":{hue}}},\
+{{\"trait_type\":\"Owner\",\"value\":\"{owner}\"}}]}}"
+    )
+}
+
+// These methods are external to other contracts
+#[public]
+impl NftArt {
+    /// Links the generator to its NFT contract (can only be called once,
+    /// by the NFT contract itself)
+    pub fn initialize(&mut self, token_contract_address: Address) -> Result<(), NftArtError> {
+        if !self.token_contract_address.get().is_zero() {
+            return Err(NftArtError::AlreadyInitialized(AlreadyInitialized {}));
+        }
+        if token_contract_address.is_zero() {
+            return Err(NftArtError::InvalidTokenContract(InvalidTokenContract {}));
+        }
+        let sender = self.vm().msg_sender();
+        if sender != token_contract_address {
+            return Err(NftArtError::UnauthorizedInitializer(UnauthorizedInitializer {
+                sender,
+                token_contract_address,
+            }));
+        }
+        self.token_contract_address.set(token_contract_address);
+        Ok(())
+    }
+
+    /// NFT contract this generator draws for
+    pub fn token_contract(&self) -> Address {
+        self.token_contract_address.get()
+    }
+
+    /// Returns the metadata URI of `token_id`. The picture only depends on the
+    /// token id, while the metadata also names its current `owner`.
+    pub fn generate_art(&self, token_id: U256, owner: Address) -> String {
+        let json = metadata_json(token_id, owner);
+        format!("data:application/json;base64,{}", base64_encode(json.as_bytes()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_primitives::address;
+    use stylus_sdk::testing::*;
+
+    const ALICE: Address = address!("0x000000000000000000000000000000000000a11c");
+    const NFT: Address = address!("0x00000000000000000000000000000000000000ff");
+
+    #[test]
+    fn test_base64_encode() {
+        assert_eq!("", base64_encode(b""));
+        assert_eq!("TQ==", base64_encode(b"M"));
+        assert_eq!("TWE=", base64_encode(b"Ma"));
+        assert_eq!("TWFu", base64_encode(b"Man"));
+        assert_eq!("P/+/", base64_encode(&[0x3f, 0xff, 0xbf]));
+    }
+
+    #[test]
+    fn test_generate_art() {
+        let vm = TestVM::default();
+        vm.set_sender(ALICE);
+        let mut art = NftArt::from(&vm);
+
+        // Only the NFT contract can link itself, and only once
+        assert!(matches!(
+            art.initialize(NFT),
+            Err(NftArtError::UnauthorizedInitializer(_))
+        ));
+        vm.set_sender(NFT);
+        assert!(art.initialize(NFT).is_ok());
+        assert!(matches!(art.initialize(NFT), Err(NftArtError::AlreadyInitialized(_))));
+        assert_eq!(NFT, art.token_contract());
+
+        // The art is deterministic and differs between ids, and the metadata names the owner
+        let uri = art.generate_art(U256::from(7), ALICE);
+        let json = metadata_json(U256::from(7), ALICE);
+        let encoded = base64_encode(json.as_bytes());
+        assert_eq!(format!("data:application/json;base64,{encoded}"), uri);
+        assert_eq!(uri, art.generate_art(U256::from(7), ALICE));
+        assert_ne!(uri, art.generate_art(U256::from(8), ALICE));
+        assert!(json.contains(&format!("{{\"trait_type\":\"Owner\",\"value\":\"{ALICE}\"}}")));
+
+        // Changing hands updates the metadata, not the picture
+        let json_for_nft = metadata_json(U256::from(7), NFT);
+        assert_ne!(json, json_for_nft);
+
+        let svg = render_svg(U256::from(7));
+        assert!(svg.starts_with("<svg ") && svg.ends_with("</svg>"));
+        assert_eq!(CIRCLES, svg.matches("<circle ").count());
+        let image = base64_encode(svg.as_bytes());
+        assert!(json.contains(&format!("\"image\":\"data:image/svg+xml;base64,{image}\"")));
+        assert!(json_for_nft.contains(&format!("\"image\":\"data:image/svg+xml;base64,{image}\"")));
+        assert!(json.starts_with("{\"name\":\"Robinhood NFT #7\""));
+    }
+}
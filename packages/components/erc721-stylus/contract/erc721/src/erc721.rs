@@ -3,29 +3,29 @@
 //! The eponymous [`Erc721`] type provides all the standard methods,
 //! and is intended to be inherited by other contract types.
 //!
-//! You can configure the behavior of [`Erc721`] via the [`Erc721Params`] trait,
-//! which allows specifying the name, symbol, and token uri.
+//! The name, symbol and base URI are kept in storage and set through
+//! [`Erc721::init`]. Token URIs are the base URI followed by the decimal
+//! token id, unless a per-token URI override is set, and every change emits
+//! an ERC-4906 metadata update event so marketplaces refresh their caches.
+//!
+//! Tokens in circulation, and the tokens of each owner, are also kept in
+//! arrays (ERC-721 Enumerable), so that they can be listed without scanning
+//! `Transfer` logs. Removals move the last token into the freed slot, so the
+//! order of these arrays changes as tokens are transferred and burned.
 //!
 //! Note that this code is unaudited and not fit for production use.
 
-use alloc::vec;
+use alloc::{format, string::String, vec};
 use core::{borrow::BorrowMut, marker::PhantomData};
 use stylus_sdk::{
     abi::Bytes,
-    evm,
-    msg,
     prelude::*,
     alloy_primitives::{Address, FixedBytes, U256}
 };
 use alloy_sol_types::sol;
 
-pub trait Erc721Params {
-    /// Immutable NFT name.
-    const NAME: &'static str;
-
-    /// Immutable NFT symbol.
-    const SYMBOL: &'static str;
-}
+/// Compile-time configuration of an [`Erc721`] token
+pub trait Erc721Params {}
 
 sol_storage! {
     /// Erc721 implements all ERC-721 methods
@@ -38,8 +38,25 @@ sol_storage! {
         mapping(uint256 => address) token_approvals;
         /// User to operator map (the operator can manage all NFTs of the owner)
         mapping(address => mapping(address => bool)) operator_approvals;
-        /// Total supply
-        uint256 total_supply;
+        /// Id of the next token to mint, i.e. the number of tokens ever minted
+        /// (burned tokens stay counted, the circulating supply is the length of `all_tokens`)
+        uint256 next_token_id;
+        /// NFT name
+        string name;
+        /// NFT symbol
+        string symbol;
+        /// Prefix of every token URI, followed by the decimal token id
+        string base_uri;
+        /// Token id to URI map, overriding the base URI for that token
+        mapping(uint256 => string) token_uris;
+        /// Ids of all the tokens in circulation
+        uint256[] all_tokens;
+        /// Token id to its index in `all_tokens`
+        mapping(uint256 => uint256) all_tokens_index;
+        /// User to the ids of the tokens they own
+        mapping(address => uint256[]) owned_tokens;
+        /// Token id to its index in its owner's `owned_tokens`
+        mapping(uint256 => uint256) owned_tokens_index;
         /// Used to allow [`Erc721Params`]
         PhantomData<T> phantom;
     }
@@ -50,6 +67,8 @@ sol! {
     event Transfer(address indexed from, address indexed to, uint256 indexed token_id);
     event Approval(address indexed owner, address indexed approved, uint256 indexed token_id);
     event ApprovalForAll(address indexed owner, address indexed operator, bool approved);
+    event MetadataUpdate(uint256 token_id);
+    event BatchMetadataUpdate(uint256 from_token_id, uint256 to_token_id);
 
     // Token id has not been minted, or it has been burned
     error InvalidTokenId(uint256 token_id);
@@ -61,6 +80,8 @@ sol! {
     error TransferToZero(uint256 token_id);
     // The receiver address refused to receive the specified token id
     error ReceiverRefused(address receiver, uint256 token_id, bytes4 returned);
+    // There is no token at the specified index of the owner's tokens (or of all tokens if zero)
+    error OutOfBoundsIndex(address owner, uint256 index);
 }
 
 /// Represents the ways methods may fail.
@@ -71,6 +92,7 @@ pub enum Erc721Error {
     NotApproved(NotApproved),
     TransferToZero(TransferToZero),
     ReceiverRefused(ReceiverRefused),
+    OutOfBoundsIndex(OutOfBoundsIndex),
 }
 
 // External interfaces
@@ -87,6 +109,33 @@ const ERC721_TOKEN_RECEIVER_ID: u32 = 0x150b7a02;
 // These methods aren't public, but are helpers used by public methods.
 // Methods marked as "pub" here are usable outside of the erc721 module (i.e. they're callable from lib.rs).
 impl<T: Erc721Params> Erc721<T> {
+    /// Sets the NFT name, symbol and base URI
+    /// (invoked by the external init() function of the inheriting contract)
+    pub fn init(&mut self, name: String, symbol: String, base_uri: String) {
+        self.name.set_str(name);
+        self.symbol.set_str(symbol);
+        self.base_uri.set_str(base_uri);
+    }
+
+    /// Sets the prefix of every token URI, signalling that all metadata changed
+    /// (access control is left to the inheriting contract)
+    pub fn set_base_uri(&mut self, base_uri: String) {
+        self.base_uri.set_str(base_uri);
+        log(self.vm(), BatchMetadataUpdate {
+            from_token_id: U256::ZERO,
+            to_token_id: U256::MAX,
+        });
+    }
+
+    /// Overrides the URI of `token_id` (an empty `uri` restores the base URI one)
+    /// (access control is left to the inheriting contract)
+    pub fn set_token_uri(&mut self, token_id: U256, uri: String) -> Result<(), Erc721Error> {
+        self.owner_of(token_id)?;
+        self.token_uris.setter(token_id).set_str(uri);
+        log(self.vm(), MetadataUpdate { token_id });
+        Ok(())
+    }
+
     /// Requires that msg::sender() is authorized to spend a given token
     fn require_authorized_to_spend(
         &self,
@@ -104,28 +153,69 @@ impl<T: Erc721Params> Erc721<T> {
         }
 
         // caller is the owner
-        if msg::sender() == owner {
+        let sender = self.vm().msg_sender();
+        if sender == owner {
             return Ok(());
         }
 
         // caller is an operator for the owner (can manage their tokens)
-        if self.operator_approvals.getter(owner).get(msg::sender()) {
+        if self.operator_approvals.getter(owner).get(sender) {
             return Ok(());
         }
 
         // caller is approved to manage this token_id
-        if msg::sender() == self.token_approvals.get(token_id) {
+        if sender == self.token_approvals.get(token_id) {
             return Ok(());
         }
 
         // otherwise, caller is not allowed to manage this token_id
         Err(Erc721Error::NotApproved(NotApproved {
             owner,
-            spender: msg::sender(),
+            spender: sender,
             token_id,
         }))
     }
 
+    /// Appends `token_id` to the tokens in circulation
+    fn add_token_to_all_tokens_enumeration(&mut self, token_id: U256) {
+        self.all_tokens_index.insert(token_id, U256::from(self.all_tokens.len()));
+        self.all_tokens.push(token_id);
+    }
+
+    /// Removes `token_id` from the tokens in circulation, moving the last token into its slot
+    fn remove_token_from_all_tokens_enumeration(&mut self, token_id: U256) {
+        let token_index = self.all_tokens_index.get(token_id);
+        let last_token_id = self.all_tokens.get(self.all_tokens.len() - 1).unwrap_or_default();
+        if let Some(mut slot) = self.all_tokens.setter(token_index) {
+            slot.set(last_token_id);
+        }
+        self.all_tokens_index.insert(last_token_id, token_index);
+
+        self.all_tokens.erase_last();
+        self.all_tokens_index.delete(token_id);
+    }
+
+    /// Appends `token_id` to the tokens of `owner`
+    fn add_token_to_owner_enumeration(&mut self, owner: Address, token_id: U256) {
+        let mut owned_tokens = self.owned_tokens.setter(owner);
+        self.owned_tokens_index.insert(token_id, U256::from(owned_tokens.len()));
+        owned_tokens.push(token_id);
+    }
+
+    /// Removes `token_id` from the tokens of `owner`, moving their last token into its slot
+    fn remove_token_from_owner_enumeration(&mut self, owner: Address, token_id: U256) {
+        let mut owned_tokens = self.owned_tokens.setter(owner);
+        let token_index = self.owned_tokens_index.get(token_id);
+        let last_token_id = owned_tokens.get(owned_tokens.len() - 1).unwrap_or_default();
+        if let Some(mut slot) = owned_tokens.setter(token_index) {
+            slot.set(last_token_id);
+        }
+        self.owned_tokens_index.insert(last_token_id, token_index);
+
+        owned_tokens.erase_last();
+        self.owned_tokens_index.delete(token_id);
+    }
+
     /// Transfers `token_id` from `from` to `to`.
     /// This function does check that `from` is the owner of the token, but it does not check
     /// that `to` is not the zero address, as this function is usable for burning.
@@ -158,23 +248,38 @@ impl<T: Erc721Params> Erc721<T> {
         // cleaning app the approved mapping for this token
         self.token_approvals.delete(token_id);
 
-        evm::log(Transfer { from, to, token_id });
+        // keeping the enumerations in sync: minting adds the token to the circulating ones,
+        // and burning removes it
+        if from.is_zero() {
+            self.add_token_to_all_tokens_enumeration(token_id);
+        } else {
+            self.remove_token_from_owner_enumeration(from, token_id);
+        }
+        if to.is_zero() {
+            self.remove_token_from_all_tokens_enumeration(token_id);
+        } else {
+            self.add_token_to_owner_enumeration(to, token_id);
+        }
+
+        log(self.vm(), Transfer { from, to, token_id });
         Ok(())
     }
 
     /// Calls `onERC721Received` on the `to` address if it is a contract.
     /// Otherwise it does nothing
-    fn call_receiver<S: TopLevelStorage>(
+    fn call_receiver<S: TopLevelStorage + BorrowMut<Self>>(
         storage: &mut S,
         token_id: U256,
         from: Address,
         to: Address,
         data: Vec<u8>,
     ) -> Result<(), Erc721Error> {
-        if to.has_code() {
+        let this = storage.borrow_mut();
+        let operator = this.vm().msg_sender();
+        if this.vm().code_size(to) > 0 {
             let receiver = IERC721TokenReceiver::new(to);
             let received = receiver
-                .on_erc_721_received(&mut *storage, msg::sender(), from, token_id, data.into())
+                .on_erc_721_received(&mut *storage, operator, from, token_id, data.into())
                 .map_err(|_e| {
                     Erc721Error::ReceiverRefused(ReceiverRefused {
                         receiver: receiver.address,
@@ -207,31 +312,32 @@ impl<T: Erc721Params> Erc721<T> {
         Self::call_receiver(storage, token_id, from, to, data)
     }
 
-    /// Mints a new token and transfers it to `to`
-    pub fn mint(&mut self, to: Address) -> Result<(), Erc721Error> {
-        let new_token_id = self.total_supply.get();
-        self.total_supply.set(new_token_id + U256::from(1u8));
+    /// Mints a new token and transfers it to `to`, returning its id
+    pub fn mint(&mut self, to: Address) -> Result<U256, Erc721Error> {
+        let new_token_id = self.next_token_id.get();
+        self.next_token_id.set(new_token_id + U256::from(1u8));
         self.transfer(new_token_id, Address::default(), to)?;
-        Ok(())
+        Ok(new_token_id)
     }
 
-    /// Mints a new token, and safe_transfers it to `to`
+    /// Mints a new token, and safe_transfers it to `to`, returning its id
     pub fn safe_mint<S: TopLevelStorage + BorrowMut<Self>>(
         storage: &mut S,
         to: Address,
         data: Vec<u8>,
-    ) -> Result<(), Erc721Error> {
+    ) -> Result<U256, Erc721Error> {
         let this = storage.borrow_mut();
-        let new_token_id = this.total_supply.get();
-        this.total_supply.set(new_token_id + U256::from(1u8));
+        let new_token_id = this.next_token_id.get();
+        this.next_token_id.set(new_token_id + U256::from(1u8));
         Self::safe_transfer(storage, new_token_id, Address::default(), to, data)?;
-        Ok(())
+        Ok(new_token_id)
     }
 
     /// Burns the token `token_id` from `from`
-    /// Note that total_supply is not reduced since it's used to calculate the next token_id to mint
+    /// Note that its id is never reused, as next_token_id is not reduced
     pub fn burn(&mut self, from: Address, token_id: U256) -> Result<(), Erc721Error> {
         self.transfer(token_id, from, Address::default())?;
+        self.token_uris.delete(token_id);
         Ok(())
     }
 }
@@ -239,14 +345,65 @@ impl<T: Erc721Params> Erc721<T> {
 // these methods are public to other contracts
 #[public]
 impl<T: Erc721Params> Erc721<T> {
-    /// Immutable NFT name.
-    pub fn name() -> Result<String, Erc721Error> {
-        Ok(T::NAME.into())
+    /// NFT name.
+    pub fn name(&self) -> Result<String, Erc721Error> {
+        Ok(self.name.get_string())
+    }
+
+    /// NFT symbol.
+    pub fn symbol(&self) -> Result<String, Erc721Error> {
+        Ok(self.symbol.get_string())
+    }
+
+    /// Prefix of every token URI without an override.
+    pub fn base_uri(&self) -> Result<String, Erc721Error> {
+        Ok(self.base_uri.get_string())
+    }
+
+    /// Metadata URI of an NFT: its override if set, or the base URI followed by the token id.
+    #[selector(name = "tokenURI")]
+    pub fn token_uri(&self, token_id: U256) -> Result<String, Erc721Error> {
+        self.owner_of(token_id)?;
+
+        let token_uri = self.token_uris.getter(token_id).get_string();
+        if !token_uri.is_empty() {
+            return Ok(token_uri);
+        }
+        let base_uri = self.base_uri.get_string();
+        if base_uri.is_empty() {
+            return Ok(base_uri);
+        }
+        Ok(format!("{base_uri}{token_id}"))
+    }
+
+    /// Number of NFTs in circulation.
+    pub fn total_supply(&self) -> Result<U256, Erc721Error> {
+        Ok(U256::from(self.all_tokens.len()))
+    }
+
+    /// Number of NFTs ever minted, including burned ones.
+    pub fn total_minted(&self) -> Result<U256, Erc721Error> {
+        Ok(self.next_token_id.get())
+    }
+
+    /// Gets the id of the NFT at `index` among all the NFTs in circulation.
+    pub fn token_by_index(&self, index: U256) -> Result<U256, Erc721Error> {
+        self.all_tokens.get(index).ok_or(Erc721Error::OutOfBoundsIndex(OutOfBoundsIndex {
+            owner: Address::ZERO,
+            index,
+        }))
     }
 
-    /// Immutable NFT symbol.
-    pub fn symbol() -> Result<String, Erc721Error> {
-        Ok(T::SYMBOL.into())
+    /// Gets the id of the NFT at `index` among the NFTs owned by an account.
+    pub fn token_of_owner_by_index(
+        &self,
+        owner: Address,
+        index: U256,
+    ) -> Result<U256, Erc721Error> {
+        self.owned_tokens
+            .getter(owner)
+            .get(index)
+            .ok_or(Erc721Error::OutOfBoundsIndex(OutOfBoundsIndex { owner, index }))
     }
 
     /// Gets the number of NFTs owned by an account.
@@ -317,16 +474,17 @@ impl<T: Erc721Params> Erc721<T> {
         let owner = self.owner_of(token_id)?;
 
         // require authorization
-        if msg::sender() != owner && !self.operator_approvals.getter(owner).get(msg::sender()) {
+        let sender = self.vm().msg_sender();
+        if sender != owner && !self.operator_approvals.getter(owner).get(sender) {
             return Err(Erc721Error::NotApproved(NotApproved {
                 owner,
-                spender: msg::sender(),
+                spender: sender,
                 token_id,
             }));
         }
         self.token_approvals.insert(token_id, approved);
 
-        evm::log(Approval {
+        log(self.vm(), Approval {
             approved,
             owner,
             token_id,
@@ -340,12 +498,12 @@ impl<T: Erc721Params> Erc721<T> {
         operator: Address,
         approved: bool,
     ) -> Result<(), Erc721Error> {
-        let owner = msg::sender();
+        let owner = self.vm().msg_sender();
         self.operator_approvals
             .setter(owner)
             .insert(operator, approved);
 
-        evm::log(ApprovalForAll {
+        log(self.vm(), ApprovalForAll {
             owner,
             operator,
             approved,
@@ -379,10 +537,12 @@ impl<T: Erc721Params> Erc721<T> {
         const IERC165: u32 = 0x01ffc9a7;
         const IERC721: u32 = 0x80ac58cd;
         const IERC721_METADATA: u32 = 0x5b5e139f;
+        const IERC721_ENUMERABLE: u32 = 0x780e9d63;
+        const IERC4906: u32 = 0x49064906;
 
         Ok(matches!(
             u32::from_be_bytes(interface_slice_array),
-            IERC165 | IERC721 | IERC721_METADATA
+            IERC165 | IERC721 | IERC721_METADATA | IERC721_ENUMERABLE | IERC4906
         ))
     }
 }
\ No newline at end of file
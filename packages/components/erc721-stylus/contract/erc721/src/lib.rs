@@ -5,31 +5,35 @@ mod erc721;
 
 /// Import the Stylus SDK along with alloy primitive types for use in our program.
 use stylus_sdk::{
-    abi::Bytes,
-    call::Call,
-    contract,
-    msg,
     prelude::*,
-    alloy_primitives::{Address, U256}
+    alloy_primitives::{b256, Address, FixedBytes, U256},
+    stylus_core::calls::context::Call,
 };
-use alloy_sol_types::sol;
-use crate::erc721::{Erc721, Erc721Params};
+use alloc::{string::String, vec::Vec};
+use alloy_sol_types::{sol, SolCall};
+use stylus_common::{access_control::{AccessControl, DEFAULT_ADMIN_ROLE}, royalty::Royalty};
+use crate::erc721::{BatchMetadataUpdate, Erc721, Erc721Params};
 
-// Interfaces for the Art contract and the ERC20 contract
-sol_interface! {
+/// `keccak256("MINTER_ROLE")`, required to mint NFTs
+const MINTER_ROLE: FixedBytes<32> =
+    b256!("9f2df0fed2c77648de5860a4cc508cd0818c85b8b8a1ab4ceeef8d981c8956a6");
+
+/// `keccak256("URI_SETTER_ROLE")`, required to update token URIs
+const URI_SETTER_ROLE: FixedBytes<32> =
+    b256!("7804d923f43a17d325d77e781528e0793b2edd9890ab45fc64efd7b4b427744c");
+
+// Interface of the art contract (see `contract/art`)
+sol! {
     interface NftArt {
         function initialize(address token_contract_address) external;
-        function generateArt(uint256 token_id, address owner) external returns(string);
+        function generateArt(uint256 token_id, address owner) external view returns(string);
     }
 }
 
 struct RobinhoodNFTParams;
 
 /// Immutable definitions
-impl Erc721Params for RobinhoodNFTParams {
-    const NAME: &'static str = "RobinhoodNFT";
-    const SYMBOL: &'static str = "RHNFT";
-}
+impl Erc721Params for RobinhoodNFTParams {}
 
 // Define the entrypoint as a Solidity storage object. The sol_storage! macro
 // will generate Rust-equivalent structs with all fields mapped to Solidity-equivalent
@@ -41,6 +45,14 @@ sol_storage! {
 
         #[borrow] // Allows erc721 to access MyToken's storage and make calls
         Erc721<RobinhoodNFTParams> erc721;
+
+        #[borrow] // Allows access_control to expose has_role(), grant_role(), revoke_role() and renounce_role()
+        AccessControl access_control;
+
+        #[borrow] // Allows royalty to expose royalty_info()
+        Royalty royalty;
+
+        bool initialized;
     }
 }
 
@@ -50,6 +62,10 @@ sol! {
     error AlreadyInitialized();
     /// A call to an external contract failed
     error ExternalCallFailed();
+    /// Token URIs are generated by the art contract, so they can't be updated
+    error ArtContractSet();
+    /// The art contract is the zero address or has no code
+    error InvalidArtContract(address art_contract);
 }
 
 /// Represents the ways methods may fail.
@@ -57,34 +73,485 @@ sol! {
 pub enum RobinhoodNFTError {
     AlreadyInitialized(AlreadyInitialized),
     ExternalCallFailed(ExternalCallFailed),
+    ArtContractSet(ArtContractSet),
+    InvalidArtContract(InvalidArtContract),
+}
+
+// These methods aren't exposed to other contracts
+impl RobinhoodNFT {
+    /// Requires that token URIs are not generated by an art contract
+    fn require_no_art_contract(&self) -> Result<(), RobinhoodNFTError> {
+        if !self.art_contract_address.get().is_zero() {
+            return Err(RobinhoodNFTError::ArtContractSet(ArtContractSet {}));
+        }
+        Ok(())
+    }
 }
 
 #[public]
-#[inherit(Erc721<RobinhoodNFTParams>)]
+#[inherit(Erc721<RobinhoodNFTParams>, AccessControl, Royalty)]
 impl RobinhoodNFT {
-    /// Mints an NFT, but does not call onErc712Received
-    pub fn mint(&mut self) -> Result<(), Vec<u8>> {
-        let minter = msg::sender();
-        self.erc721.mint(minter)?;
+    /// Sets the collection metadata and grants the admin, minter and URI setter roles
+    /// to the caller (can only be called once)
+    pub fn init(&mut self, name: String, symbol: String, base_uri: String) -> Result<(), Vec<u8>> {
+        if self.initialized.get() {
+            return Err(RobinhoodNFTError::AlreadyInitialized(AlreadyInitialized {}).into());
+        }
+        self.initialized.set(true);
+        self.erc721.init(name, symbol, base_uri);
+
+        let admin = self.vm().msg_sender();
+        self.access_control._grant_role(DEFAULT_ADMIN_ROLE, admin);
+        self.access_control._grant_role(MINTER_ROLE, admin);
+        self.access_control._grant_role(URI_SETTER_ROLE, admin);
         Ok(())
     }
 
-    /// Mints an NFT to the specified address, and does not call onErc712Received
-    pub fn mint_to(&mut self, to: Address) -> Result<(), Vec<u8>> {
-        self.erc721.mint(to)?;
+    /// Links the contract generating every token URI, initializes it with this
+    /// contract's address and signals that all metadata changed
+    /// (only callable once, by admins)
+    pub fn set_art_contract(&mut self, art_contract: Address) -> Result<(), Vec<u8>> {
+        self.access_control.only_role(DEFAULT_ADMIN_ROLE)?;
+        if !self.art_contract_address.get().is_zero() {
+            return Err(RobinhoodNFTError::AlreadyInitialized(AlreadyInitialized {}).into());
+        }
+        if art_contract.is_zero() || self.vm().code_size(art_contract) == 0 {
+            return Err(RobinhoodNFTError::InvalidArtContract(InvalidArtContract { art_contract }).into());
+        }
+        self.art_contract_address.set(art_contract);
+
+        let calldata = NftArt::initializeCall {
+            token_contract_address: self.vm().contract_address(),
+        }
+        .abi_encode();
+        self.vm()
+            .call(&Call::new(), art_contract, &calldata)
+            .map_err(|_| RobinhoodNFTError::ExternalCallFailed(ExternalCallFailed {}))?;
+        log(self.vm(), BatchMetadataUpdate {
+            from_token_id: U256::ZERO,
+            to_token_id: U256::MAX,
+        });
         Ok(())
     }
 
-    /// Mints an NFT and calls onErc712Received with empty data
-    pub fn safe_mint(&mut self, to: Address) -> Result<(), Vec<u8>> {
-        Erc721::safe_mint(self, to, Vec::new())?;
+    /// Contract generating every token URI, if any
+    pub fn art_contract(&self) -> Address {
+        self.art_contract_address.get()
+    }
+
+    /// Gets the metadata URI of an NFT, generated by the art contract once it is set
+    #[selector(name = "tokenURI")]
+    pub fn token_uri(&self, token_id: U256) -> Result<String, Vec<u8>> {
+        let art_contract = self.art_contract_address.get();
+        if art_contract.is_zero() {
+            return Ok(self.erc721.token_uri(token_id)?);
+        }
+
+        let owner = self.erc721.owner_of(token_id)?;
+        let calldata = NftArt::generateArtCall { token_id, owner }.abi_encode();
+        let uri = self
+            .vm()
+            .static_call(&Call::new(), art_contract, &calldata)
+            .ok()
+            .and_then(|returned| NftArt::generateArtCall::abi_decode_returns(&returned, true).ok())
+            .ok_or(RobinhoodNFTError::ExternalCallFailed(ExternalCallFailed {}))?;
+        Ok(uri._0)
+    }
+
+    /// Whether the NFT supports a given standard, including ERC-2981 royalties
+    pub fn supports_interface(interface: FixedBytes<4>) -> Result<bool, Vec<u8>> {
+        let supported = Erc721::<RobinhoodNFTParams>::supports_interface(interface)?;
+        Ok(supported || Royalty::supports_interface(interface))
+    }
+
+    /// Role required to mint NFTs
+    #[selector(name = "MINTER_ROLE")]
+    pub fn minter_role() -> FixedBytes<32> {
+        MINTER_ROLE
+    }
+
+    /// Role required to update token URIs
+    #[selector(name = "URI_SETTER_ROLE")]
+    pub fn uri_setter_role() -> FixedBytes<32> {
+        URI_SETTER_ROLE
+    }
+
+    /// Updates the prefix of every token URI
+    /// (only callable by URI setters, until an art contract is set)
+    pub fn set_base_uri(&mut self, base_uri: String) -> Result<(), Vec<u8>> {
+        self.access_control.only_role(URI_SETTER_ROLE)?;
+        self.require_no_art_contract()?;
+        self.erc721.set_base_uri(base_uri);
+        Ok(())
+    }
+
+    /// Overrides the URI of a single NFT, or restores it with an empty URI
+    /// (only callable by URI setters, until an art contract is set)
+    pub fn set_token_uri(&mut self, token_id: U256, uri: String) -> Result<(), Vec<u8>> {
+        self.access_control.only_role(URI_SETTER_ROLE)?;
+        self.require_no_art_contract()?;
+        self.erc721.set_token_uri(token_id, uri)?;
+        Ok(())
+    }
+
+    /// Sets the ERC-2981 royalty of every NFT without an override, with `fee_bps`
+    /// in basis points of the sale price (only callable by admins)
+    pub fn set_default_royalty(&mut self, receiver: Address, fee_bps: U256) -> Result<(), Vec<u8>> {
+        self.access_control.only_role(DEFAULT_ADMIN_ROLE)?;
+        self.royalty.set_default_royalty(receiver, fee_bps)?;
+        Ok(())
+    }
+
+    /// Removes the default royalty (only callable by admins)
+    pub fn delete_default_royalty(&mut self) -> Result<(), Vec<u8>> {
+        self.access_control.only_role(DEFAULT_ADMIN_ROLE)?;
+        self.royalty.delete_default_royalty();
         Ok(())
     }
 
+    /// Overrides the royalty of a single NFT (only callable by admins)
+    pub fn set_token_royalty(
+        &mut self,
+        token_id: U256,
+        receiver: Address,
+        fee_bps: U256,
+    ) -> Result<(), Vec<u8>> {
+        self.access_control.only_role(DEFAULT_ADMIN_ROLE)?;
+        self.royalty.set_token_royalty(token_id, receiver, fee_bps)?;
+        Ok(())
+    }
+
+    /// Makes a single NFT pay the default royalty again (only callable by admins)
+    pub fn reset_token_royalty(&mut self, token_id: U256) -> Result<(), Vec<u8>> {
+        self.access_control.only_role(DEFAULT_ADMIN_ROLE)?;
+        self.royalty.reset_token_royalty(token_id);
+        Ok(())
+    }
+
+    /// Mints an NFT, but does not call onErc712Received, and returns its id
+    /// (only callable by minters)
+    pub fn mint(&mut self) -> Result<U256, Vec<u8>> {
+        self.access_control.only_role(MINTER_ROLE)?;
+        let minter = self.vm().msg_sender();
+        Ok(self.erc721.mint(minter)?)
+    }
+
+    /// Mints an NFT to the specified address, and does not call onErc712Received,
+    /// and returns its id (only callable by minters)
+    pub fn mint_to(&mut self, to: Address) -> Result<U256, Vec<u8>> {
+        self.access_control.only_role(MINTER_ROLE)?;
+        Ok(self.erc721.mint(to)?)
+    }
+
+    /// Mints an NFT and calls onErc712Received with empty data, and returns its id
+    /// (only callable by minters)
+    pub fn safe_mint(&mut self, to: Address) -> Result<U256, Vec<u8>> {
+        self.access_control.only_role(MINTER_ROLE)?;
+        Ok(Erc721::safe_mint(self, to, Vec::new())?)
+    }
+
     /// Burns an NFT
     pub fn burn(&mut self, token_id: U256) -> Result<(), Vec<u8>> {
         // This function checks that msg::sender() owns the specified token_id
-        self.erc721.burn(msg::sender(), token_id)?;
+        self.erc721.burn(self.vm().msg_sender(), token_id)?;
         Ok(())
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use alloy_primitives::address;
+    use stylus_sdk::testing::*;
+
+    const ALICE: Address = address!("0x000000000000000000000000000000000000a11c");
+    const BOB: Address = address!("0x0000000000000000000000000000000000000b0b");
+
+    #[test]
+    fn test_only_minter_mints() {
+        let vm = TestVM::default();
+        vm.set_sender(ALICE);
+        let mut contract = RobinhoodNFT::from(&vm);
+        assert!(contract.init("Robinhood".into(), "RHNFT".into(), String::new()).is_ok());
+        assert!(contract.init("Other".into(), "OTH".into(), String::new()).is_err());
+
+        assert!(contract.mint_to(BOB).is_ok());
+        assert_eq!(Ok(BOB), contract.erc721.owner_of(U256::ZERO).map_err(|_| ()));
+
+        vm.set_sender(BOB);
+        assert!(contract.mint().is_err());
+        assert!(contract.mint_to(BOB).is_err());
+
+        vm.set_sender(ALICE);
+        assert!(contract.access_control.grant_role(MINTER_ROLE, BOB).is_ok());
+        vm.set_sender(BOB);
+        assert!(contract.mint().is_ok());
+        assert_eq!(U256::from(2), contract.erc721.balance_of(BOB).unwrap_or_default());
+        assert!(contract.access_control.has_role(DEFAULT_ADMIN_ROLE, ALICE));
+    }
+
+    #[test]
+    fn test_enumerable() {
+        let vm = TestVM::default();
+        vm.set_sender(ALICE);
+        let mut contract = RobinhoodNFT::from(&vm);
+        assert!(contract.init("Robinhood".into(), "RHNFT".into(), String::new()).is_ok());
+        for to in [BOB, BOB, BOB, ALICE] {
+            assert!(contract.mint_to(to).is_ok());
+        }
+
+        let tokens_of = |contract: &RobinhoodNFT, owner: Address| -> Vec<u64> {
+            let balance = contract.erc721.balance_of(owner).unwrap_or_default();
+            (0..balance.to::<u64>())
+                .map(|i| {
+                    let token_id = contract.erc721.token_of_owner_by_index(owner, U256::from(i));
+                    token_id.map(|id| id.to::<u64>()).unwrap_or(u64::MAX)
+                })
+                .collect()
+        };
+        let all_tokens = |contract: &RobinhoodNFT| -> Vec<u64> {
+            let total_supply = contract.erc721.total_supply().unwrap_or_default();
+            (0..total_supply.to::<u64>())
+                .map(|i| {
+                    let token_id = contract.erc721.token_by_index(U256::from(i));
+                    token_id.map(|id| id.to::<u64>()).unwrap_or(u64::MAX)
+                })
+                .collect()
+        };
+        assert_eq!(vec![0, 1, 2], tokens_of(&contract, BOB));
+        assert_eq!(vec![0, 1, 2, 3], all_tokens(&contract));
+
+        // Transfers move the last token of the sender into the freed slot
+        vm.set_sender(BOB);
+        assert!(contract.erc721.transfer_from(BOB, ALICE, U256::ZERO).is_ok());
+        assert_eq!(vec![2, 1], tokens_of(&contract, BOB));
+        assert_eq!(vec![3, 0], tokens_of(&contract, ALICE));
+
+        // Burns also remove the token from circulation, but ids are never reused
+        assert!(contract.burn(U256::from(2)).is_ok());
+        assert_eq!(vec![1], tokens_of(&contract, BOB));
+        assert_eq!(vec![0, 1, 3], all_tokens(&contract));
+        vm.set_sender(ALICE);
+        assert!(contract.burn(U256::ZERO).is_ok());
+        assert_eq!(vec![3], tokens_of(&contract, ALICE));
+        assert_eq!(vec![3, 1], all_tokens(&contract));
+
+        assert!(matches!(
+            contract.erc721.token_by_index(U256::from(2)),
+            Err(crate::erc721::Erc721Error::OutOfBoundsIndex(_))
+        ));
+        assert!(matches!(
+            contract.erc721.token_of_owner_by_index(BOB, U256::from(1)),
+            Err(crate::erc721::Erc721Error::OutOfBoundsIndex(_))
+        ));
+
+        // The supply only counts tokens in circulation, while minted ids keep growing
+        assert_eq!(Ok(U256::from(2)), contract.erc721.total_supply().map_err(|_| ()));
+        assert_eq!(Ok(U256::from(4)), contract.erc721.total_minted().map_err(|_| ()));
+        assert_eq!(Ok(U256::from(4)), contract.mint().map_err(|_| ()));
+        assert_eq!(Ok(U256::from(5)), contract.erc721.total_minted().map_err(|_| ()));
+        assert_eq!(vec![3, 1, 4], all_tokens(&contract));
+
+        let ierc721_enumerable = FixedBytes::new([0x78, 0x0e, 0x9d, 0x63]);
+        type Nft = Erc721<RobinhoodNFTParams>;
+        assert_eq!(Ok(true), Nft::supports_interface(ierc721_enumerable).map_err(|_| ()));
+    }
+
+    #[test]
+    fn test_royalty() {
+        let vm = TestVM::default();
+        vm.set_sender(ALICE);
+        let mut contract = RobinhoodNFT::from(&vm);
+        assert!(contract.init("Robinhood".into(), "RHNFT".into(), String::new()).is_ok());
+
+        // No royalty until one is set
+        let price = U256::from(1_000_000);
+        assert_eq!((Address::ZERO, U256::ZERO), contract.royalty.royalty_info(U256::ZERO, price));
+
+        // Only admins can set royalties, of at most 100% and with a receiver
+        vm.set_sender(BOB);
+        assert!(contract.set_default_royalty(BOB, U256::from(500)).is_err());
+        vm.set_sender(ALICE);
+        assert!(contract.set_default_royalty(ALICE, U256::from(10_001)).is_err());
+        assert!(contract.set_default_royalty(Address::ZERO, U256::from(500)).is_err());
+        assert!(contract.set_default_royalty(ALICE, U256::from(500)).is_ok());
+        assert!(contract.set_token_royalty(U256::from(1), BOB, U256::from(10_000)).is_ok());
+
+        // Overrides take precedence over the default royalty, until they are reset
+        let royalty_info = |contract: &RobinhoodNFT, id: u64| {
+            contract.royalty.royalty_info(U256::from(id), price)
+        };
+        assert_eq!((ALICE, U256::from(50_000)), royalty_info(&contract, 0));
+        assert_eq!((BOB, price), royalty_info(&contract, 1));
+        assert!(contract.reset_token_royalty(U256::from(1)).is_ok());
+        assert_eq!((ALICE, U256::from(50_000)), royalty_info(&contract, 1));
+        assert!(contract.delete_default_royalty().is_ok());
+        assert_eq!((Address::ZERO, U256::ZERO), royalty_info(&contract, 1));
+
+        // Large prices don't overflow
+        assert!(contract.set_default_royalty(ALICE, U256::from(250)).is_ok());
+        let (_, amount) = contract.royalty.royalty_info(U256::ZERO, U256::MAX);
+        assert_eq!(U256::MAX / U256::from(40), amount);
+
+        // Royalties are advertised by the collection, not by the generic Erc721
+        let ierc2981 = FixedBytes::new([0x2a, 0x55, 0x20, 0x5a]);
+        type Nft = Erc721<RobinhoodNFTParams>;
+        assert_eq!(Ok(false), Nft::supports_interface(ierc2981).map_err(|_| ()));
+        assert_eq!(Ok(true), RobinhoodNFT::supports_interface(ierc2981));
+        let ierc721 = FixedBytes::new([0x80, 0xac, 0x58, 0xcd]);
+        assert_eq!(Ok(true), RobinhoodNFT::supports_interface(ierc721));
+        assert_eq!(Ok(false), RobinhoodNFT::supports_interface(FixedBytes::new([0xff; 4])));
+    }
+
+    #[test]
+    fn test_token_uri() {
+        use alloy_primitives::keccak256;
+
+        let vm = TestVM::default();
+        vm.set_sender(ALICE);
+        let mut contract = RobinhoodNFT::from(&vm);
+        assert_eq!(keccak256("URI_SETTER_ROLE"), RobinhoodNFT::uri_setter_role());
+        assert!(contract
+            .init("Robinhood".into(), "RHNFT".into(), "ipfs://collection/".into())
+            .is_ok());
+        assert_eq!(Ok("Robinhood".into()), contract.erc721.name().map_err(|_| ()));
+        assert_eq!(Ok("RHNFT".into()), contract.erc721.symbol().map_err(|_| ()));
+
+        // Unminted ids have no URI
+        assert!(matches!(
+            contract.erc721.token_uri(U256::from(12)),
+            Err(crate::erc721::Erc721Error::InvalidTokenId(_))
+        ));
+        for _ in 0..13 {
+            assert!(contract.mint_to(BOB).is_ok());
+        }
+        let token_uri = |contract: &RobinhoodNFT, id: u64| {
+            contract.erc721.token_uri(U256::from(id)).unwrap_or_default()
+        };
+        assert_eq!("ipfs://collection/12", token_uri(&contract, 12));
+
+        // Only URI setters can change URIs, and overrides take precedence over the base URI
+        vm.set_sender(BOB);
+        assert!(contract.set_base_uri("https://example.com/".into()).is_err());
+        assert!(contract.set_token_uri(U256::from(3), "ar://three".into()).is_err());
+        vm.set_sender(ALICE);
+        assert!(contract.set_base_uri("https://example.com/".into()).is_ok());
+        assert!(contract.set_token_uri(U256::from(3), "ar://three".into()).is_ok());
+        assert!(contract.set_token_uri(U256::from(13), "ar://none".into()).is_err());
+        assert_eq!("https://example.com/12", token_uri(&contract, 12));
+        assert_eq!("ar://three", token_uri(&contract, 3));
+        assert!(contract.set_token_uri(U256::from(3), String::new()).is_ok());
+        assert_eq!("https://example.com/3", token_uri(&contract, 3));
+
+        // Roles are unaffected by the metadata stored before them
+        assert!(contract.access_control.has_role(MINTER_ROLE, ALICE));
+        assert!(!contract.access_control.has_role(URI_SETTER_ROLE, BOB));
+
+        // Both changes are signalled through ERC-4906 events
+        let emitted = |signature: &str| {
+            let topic = keccak256(signature);
+            vm.get_emitted_logs().iter().any(|(topics, _)| topics[0] == topic)
+        };
+        assert!(emitted("BatchMetadataUpdate(uint256,uint256)"));
+        assert!(emitted("MetadataUpdate(uint256)"));
+        let ierc4906 = FixedBytes::new([0x49, 0x06, 0x49, 0x06]);
+        type Nft = Erc721<RobinhoodNFTParams>;
+        assert_eq!(Ok(true), Nft::supports_interface(ierc4906).map_err(|_| ()));
+    }
+
+    #[test]
+    fn test_art_contract() {
+        use alloy_sol_types::{SolError, SolEvent};
+
+        let art = address!("0x00000000000000000000000000000000000a9701");
+        let vm = TestVM::default();
+        vm.set_sender(ALICE);
+        vm.set_code(art, vec![0xfe]);
+        let mut contract = RobinhoodNFT::from(&vm);
+        assert!(contract.init("Robinhood".into(), "RHNFT".into(), "ipfs://collection/".into()).is_ok());
+        assert!(contract.mint_to(BOB).is_ok());
+
+        // The art contract is told which collection it draws for
+        let initialize = NftArt::initializeCall {
+            token_contract_address: vm.contract_address(),
+        }
+        .abi_encode();
+        vm.mock_call(art, initialize, Ok(Vec::new()));
+
+        // Only admins can set it, only to a contract, and only once
+        vm.set_sender(BOB);
+        assert!(contract.set_art_contract(art).is_err());
+        assert_eq!(Address::ZERO, contract.art_contract());
+        vm.set_sender(ALICE);
+        assert_eq!(
+            Err(InvalidArtContract { art_contract: Address::ZERO }.abi_encode()),
+            contract.set_art_contract(Address::ZERO)
+        );
+        assert_eq!(
+            Err(InvalidArtContract { art_contract: BOB }.abi_encode()),
+            contract.set_art_contract(BOB)
+        );
+        assert_eq!(Address::ZERO, contract.art_contract());
+        assert!(contract.set_art_contract(art).is_ok());
+        assert_eq!(art, contract.art_contract());
+        let log = BatchMetadataUpdate {
+            from_token_id: U256::ZERO,
+            to_token_id: U256::MAX,
+        }
+        .encode_log_data();
+        assert!(vm.get_emitted_logs().contains(&(log.topics().to_vec(), log.data.to_vec())));
+        assert_eq!(
+            Err(AlreadyInitialized {}.abi_encode()),
+            contract.set_art_contract(BOB)
+        );
+
+        // Token URIs are then generated by the art contract
+        let generate_art = |token_id: u64| {
+            NftArt::generateArtCall { token_id: U256::from(token_id), owner: BOB }.abi_encode()
+        };
+        let uri = String::from("data:application/json;base64,e30=");
+        let returned = NftArt::generateArtCall::abi_encode_returns(&(uri.clone(),));
+        vm.mock_static_call(art, generate_art(0), Ok(returned));
+        assert_eq!(Ok(uri), contract.token_uri(U256::ZERO));
+
+        // A failing art contract surfaces as ExternalCallFailed
+        assert!(contract.mint_to(BOB).is_ok());
+        vm.mock_static_call(art, generate_art(1), Err(b"out of ink".to_vec()));
+        assert_eq!(
+            Err(ExternalCallFailed {}.abi_encode()),
+            contract.token_uri(U256::from(1))
+        );
+
+        // And the stored URIs can no longer change
+        assert_eq!(
+            Err(ArtContractSet {}.abi_encode()),
+            contract.set_base_uri("https://example.com/".into())
+        );
+        assert_eq!(
+            Err(ArtContractSet {}.abi_encode()),
+            contract.set_token_uri(U256::ZERO, "ar://zero".into())
+        );
+    }
+
+    #[test]
+    fn test_art_contract_initialize_failure() {
+        use alloy_sol_types::SolError;
+
+        let art = address!("0x00000000000000000000000000000000000a9701");
+        let vm = TestVM::default();
+        vm.set_sender(ALICE);
+        vm.set_code(art, vec![0xfe]);
+        let mut contract = RobinhoodNFT::from(&vm);
+        assert!(contract.init("Robinhood".into(), "RHNFT".into(), String::new()).is_ok());
+
+        let initialize = NftArt::initializeCall {
+            token_contract_address: vm.contract_address(),
+        }
+        .abi_encode();
+        vm.mock_call(art, initialize, Err(b"already initialized".to_vec()));
+        assert_eq!(
+            Err(ExternalCallFailed {}.abi_encode()),
+            contract.set_art_contract(art)
+        );
+    }
+}
@@ -189,17 +189,14 @@ export function useERC721Interactions(options: UseERC721InteractionsOptions): Us
       throw new Error('Public client is required');
     }
 
-    // Get current total supply to estimate token ID
-    const totalSupplyBefore = await publicClient.readContract({
+    // Token IDs start at 0 and are never reused, so the next one is the number minted so far
+    const tokenId = await publicClient.readContract({
       address: contractAddress,
       abi: ERC721_ABI,
-      functionName: 'totalSupply',
+      functionName: 'totalMinted',
     }) as bigint;
 
-    const hash = await executeTransaction('mint', [to]);
-    
-    // Token ID is typically totalSupply + 1 or next token ID
-    const tokenId = totalSupplyBefore + 1n;
+    const hash = await executeTransaction('mintTo', [to]);
     
     refetchCollectionInfo();
     refetchBalance();
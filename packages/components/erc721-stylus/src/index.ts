@@ -48,6 +48,7 @@ export type {
 export {
   getCollectionInfo,
   getBalance,
+  getTokensOfOwner,
   getNFTInfo,
   mint,
   transferFrom,
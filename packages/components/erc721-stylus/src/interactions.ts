@@ -56,6 +56,27 @@ export async function getBalance(
   };
 }
 
+/**
+ * Get the ids of all the NFTs owned by an address
+ */
+export async function getTokensOfOwner(
+  contractAddress: Address,
+  accountAddress: Address,
+  rpcEndpoint: string
+): Promise<bigint[]> {
+  const provider = new ethers.JsonRpcProvider(rpcEndpoint);
+  const contract = new ethers.Contract(contractAddress, ERC721_ABI, provider);
+
+  const balance = BigInt(await contract.balanceOf(accountAddress));
+  const tokenIds = await Promise.all(
+    Array.from({ length: Number(balance) }, (_, index) =>
+      contract.tokenOfOwnerByIndex(accountAddress, index)
+    )
+  );
+
+  return tokenIds.map((tokenId) => BigInt(tokenId));
+}
+
 /**
  * Get NFT information
  */
@@ -94,7 +115,7 @@ export async function mint(
   const wallet = new ethers.Wallet(privateKey, provider);
   const contract = new ethers.Contract(contractAddress, ERC721_ABI, wallet);
 
-  const tx = await contract.mint(to);
+  const tx = await contract.mintTo(to);
   const receipt = await tx.wait();
   
   // Parse the Transfer event to get the token ID
@@ -0,0 +1,158 @@
+//! Implementation of role-based access control
+//!
+//! The [`AccessControl`] type keeps track of which accounts hold which roles,
+//! and is intended to be inherited by contracts that need several privileged
+//! keys (e.g. separate minter and pauser accounts).
+//! Use [`AccessControl::only_role`] to guard privileged entrypoints.
+//!
+//! Roles are identified by a `bytes32`, usually `keccak256("<NAME>_ROLE")`.
+//! Each role has an admin role whose holders can grant and revoke it;
+//! by default this is [`DEFAULT_ADMIN_ROLE`], which is its own admin.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Imported packages
+use alloy_primitives::{Address, FixedBytes};
+use alloy_sol_types::sol;
+use stylus_sdk::prelude::*;
+
+/// Admin of every role that has no other admin configured
+pub const DEFAULT_ADMIN_ROLE: FixedBytes<32> = FixedBytes::ZERO;
+
+sol_storage! {
+    /// AccessControl tracks the members of each role.
+    pub struct AccessControl {
+        /// Maps roles to a mapping of whether each account holds them
+        mapping(bytes32 => mapping(address => bool)) members;
+        /// Maps roles to the role allowed to grant and revoke them
+        mapping(bytes32 => bytes32) admin_roles;
+    }
+}
+
+// Declare events and Solidity error types
+sol! {
+    event RoleGranted(bytes32 indexed role, address indexed account, address indexed sender);
+    event RoleRevoked(bytes32 indexed role, address indexed account, address indexed sender);
+    event RoleAdminChanged(bytes32 indexed role, bytes32 indexed previous_admin_role, bytes32 indexed new_admin_role);
+
+    error AccessControlUnauthorizedAccount(address account, bytes32 needed_role);
+    error AccessControlBadConfirmation();
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum AccessControlError {
+    UnauthorizedAccount(AccessControlUnauthorizedAccount),
+    BadConfirmation(AccessControlBadConfirmation),
+}
+
+// These methods aren't exposed to other contracts
+// Methods marked as "pub" here are usable by the contracts that inherit AccessControl
+impl AccessControl {
+    /// Requires that msg::sender() holds `role`
+    pub fn only_role(&self, role: FixedBytes<32>) -> Result<(), AccessControlError> {
+        let account = self.vm().msg_sender();
+        if !self.has_role(role, account) {
+            return Err(AccessControlError::UnauthorizedAccount(
+                AccessControlUnauthorizedAccount {
+                    account,
+                    needed_role: role,
+                },
+            ));
+        }
+        Ok(())
+    }
+
+    /// Gives `role` to `account` without any access check, returning whether it was granted
+    /// (invoked on initialization and by the external grant_role() function)
+    pub fn _grant_role(&mut self, role: FixedBytes<32>, account: Address) -> bool {
+        if self.has_role(role, account) {
+            return false;
+        }
+        self.members.setter(role).insert(account, true);
+        log(self.vm(), RoleGranted {
+            role,
+            account,
+            sender: self.vm().msg_sender(),
+        });
+        true
+    }
+
+    /// Takes `role` from `account` without any access check, returning whether it was revoked
+    /// (invoked by the external revoke_role() and renounce_role() functions)
+    pub fn _revoke_role(&mut self, role: FixedBytes<32>, account: Address) -> bool {
+        if !self.has_role(role, account) {
+            return false;
+        }
+        self.members.setter(role).insert(account, false);
+        log(self.vm(), RoleRevoked {
+            role,
+            account,
+            sender: self.vm().msg_sender(),
+        });
+        true
+    }
+
+    /// Makes `admin_role` the admin of `role`
+    pub fn _set_role_admin(&mut self, role: FixedBytes<32>, admin_role: FixedBytes<32>) {
+        let previous_admin_role = self.get_role_admin(role);
+        self.admin_roles.insert(role, admin_role);
+        log(self.vm(), RoleAdminChanged {
+            role,
+            previous_admin_role,
+            new_admin_role: admin_role,
+        });
+    }
+}
+
+// These methods are external to other contracts
+#[public]
+impl AccessControl {
+    /// Whether `account` holds `role`
+    pub fn has_role(&self, role: FixedBytes<32>, account: Address) -> bool {
+        self.members.get(role).get(account)
+    }
+
+    /// The role allowed to grant and revoke `role`
+    pub fn get_role_admin(&self, role: FixedBytes<32>) -> FixedBytes<32> {
+        self.admin_roles.get(role)
+    }
+
+    /// Gives `role` to `account` (only callable by holders of the role's admin role)
+    pub fn grant_role(
+        &mut self,
+        role: FixedBytes<32>,
+        account: Address,
+    ) -> Result<(), AccessControlError> {
+        self.only_role(self.get_role_admin(role))?;
+        self._grant_role(role, account);
+        Ok(())
+    }
+
+    /// Takes `role` from `account` (only callable by holders of the role's admin role)
+    pub fn revoke_role(
+        &mut self,
+        role: FixedBytes<32>,
+        account: Address,
+    ) -> Result<(), AccessControlError> {
+        self.only_role(self.get_role_admin(role))?;
+        self._revoke_role(role, account);
+        Ok(())
+    }
+
+    /// Gives up `role` held by msg::sender()
+    /// (`caller_confirmation` must be msg::sender(), to prevent accidental renounces)
+    pub fn renounce_role(
+        &mut self,
+        role: FixedBytes<32>,
+        caller_confirmation: Address,
+    ) -> Result<(), AccessControlError> {
+        if caller_confirmation != self.vm().msg_sender() {
+            return Err(AccessControlError::BadConfirmation(
+                AccessControlBadConfirmation {},
+            ));
+        }
+        self._revoke_role(role, caller_confirmation);
+        Ok(())
+    }
+}
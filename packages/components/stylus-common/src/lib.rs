@@ -0,0 +1,11 @@
+//! Storage modules shared by the Stylus token contracts
+//!
+//! The component contracts depend on it by path (`../../../stylus-common`), and
+//! the orchestrator copies it next to them in generated projects.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+extern crate alloc;
+
+pub mod access_control;
+pub mod royalty;
@@ -0,0 +1,134 @@
+//! Implementation of the ERC-2981 NFT royalty standard
+//!
+//! The [`Royalty`] type stores a default royalty, paid on every token, and
+//! optional per-token overrides, and is intended to be inherited by NFT
+//! contracts so that marketplaces can query `royaltyInfo`.
+//!
+//! Royalty doesn't expose `supportsInterface` itself: the inheriting contract
+//! combines [`Royalty::supports_interface`] with its other standards.
+//!
+//! Fees are in basis points of the sale price (10000 = 100%). The setters
+//! don't check access: guard them in the inheriting contract, e.g. with
+//! `AccessControl::only_role`. Royalties are only advisory: marketplaces
+//! decide whether to pay them.
+//!
+//! Note that this code is unaudited and not fit for production use.
+
+// Imported packages
+use alloy_primitives::{Address, FixedBytes, U256};
+use alloy_sol_types::sol;
+use stylus_sdk::prelude::*;
+
+/// Basis points of a whole sale price
+pub const FEE_DENOMINATOR: u64 = 10_000;
+
+/// ERC-165 identifier of IERC2981
+const IERC2981: u32 = 0x2a55205a;
+
+sol_storage! {
+    /// Royalty tracks the default royalty and the per-token overrides.
+    pub struct Royalty {
+        /// Account paid the royalty of tokens without an override
+        address default_receiver;
+        /// Fee of tokens without an override, in basis points
+        uint256 default_fee_bps;
+        /// Maps token ids to the account paid their royalty, if overridden
+        mapping(uint256 => address) token_receivers;
+        /// Maps token ids to their fee, in basis points
+        mapping(uint256 => uint256) token_fee_bps;
+    }
+}
+
+// Declare Solidity error types
+sol! {
+    error InvalidRoyalty(uint256 fee_bps, uint256 max_fee_bps);
+    error InvalidRoyaltyReceiver(address receiver);
+}
+
+/// Represents the ways methods may fail.
+#[derive(SolidityError)]
+pub enum RoyaltyError {
+    InvalidRoyalty(InvalidRoyalty),
+    InvalidRoyaltyReceiver(InvalidRoyaltyReceiver),
+}
+
+/// Requires that `fee_bps` is at most 100%, and that a fee has someone to be paid to
+fn check_royalty(receiver: Address, fee_bps: U256) -> Result<(), RoyaltyError> {
+    let max_fee_bps = U256::from(FEE_DENOMINATOR);
+    if fee_bps > max_fee_bps {
+        return Err(RoyaltyError::InvalidRoyalty(InvalidRoyalty {
+            fee_bps,
+            max_fee_bps,
+        }));
+    }
+    if receiver.is_zero() {
+        return Err(RoyaltyError::InvalidRoyaltyReceiver(InvalidRoyaltyReceiver { receiver }));
+    }
+    Ok(())
+}
+
+// These methods aren't exposed to other contracts
+// Methods marked as "pub" here are usable by the contracts that inherit Royalty
+impl Royalty {
+    /// Whether `interface` is IERC2981 (for the `supportsInterface` of the inheriting contract)
+    pub fn supports_interface(interface: FixedBytes<4>) -> bool {
+        u32::from_be_bytes(interface.0) == IERC2981
+    }
+
+    /// Sets the royalty of every token without an override
+    pub fn set_default_royalty(
+        &mut self,
+        receiver: Address,
+        fee_bps: U256,
+    ) -> Result<(), RoyaltyError> {
+        check_royalty(receiver, fee_bps)?;
+        self.default_receiver.set(receiver);
+        self.default_fee_bps.set(fee_bps);
+        Ok(())
+    }
+
+    /// Removes the default royalty, so that tokens without an override pay none
+    pub fn delete_default_royalty(&mut self) {
+        self.default_receiver.set(Address::ZERO);
+        self.default_fee_bps.set(U256::ZERO);
+    }
+
+    /// Overrides the royalty of `token_id`
+    pub fn set_token_royalty(
+        &mut self,
+        token_id: U256,
+        receiver: Address,
+        fee_bps: U256,
+    ) -> Result<(), RoyaltyError> {
+        check_royalty(receiver, fee_bps)?;
+        self.token_receivers.insert(token_id, receiver);
+        self.token_fee_bps.insert(token_id, fee_bps);
+        Ok(())
+    }
+
+    /// Removes the override of `token_id`, which pays the default royalty again
+    pub fn reset_token_royalty(&mut self, token_id: U256) {
+        self.token_receivers.delete(token_id);
+        self.token_fee_bps.delete(token_id);
+    }
+}
+
+// These methods are external to other contracts
+#[public]
+impl Royalty {
+    /// Account to pay and amount of the royalty due when `token_id` is sold for `sale_price`
+    pub fn royalty_info(&self, token_id: U256, sale_price: U256) -> (Address, U256) {
+        let mut receiver = self.token_receivers.get(token_id);
+        let mut fee_bps = self.token_fee_bps.get(token_id);
+        if receiver.is_zero() {
+            receiver = self.default_receiver.get();
+            fee_bps = self.default_fee_bps.get();
+        }
+
+        // Can't overflow, as fee_bps <= FEE_DENOMINATOR
+        let denominator = U256::from(FEE_DENOMINATOR);
+        let amount = (sale_price / denominator) * fee_bps
+            + (sale_price % denominator) * fee_bps / denominator;
+        (receiver, amount)
+    }
+}